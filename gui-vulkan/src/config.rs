@@ -0,0 +1,122 @@
+//! Settings that can be tuned without recompiling, read from a TOML file passed with `--config`. Keys missing from
+//! the file keep their defaults, and command-line flags override the file.
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use shared::colors::Classic;
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    DEFAULT_BACKGROUND_COLOR, DEFAULT_CELL_GAP, DEFAULT_GRID_WIDTH, DEFAULT_TICK_RATE, DEFAULT_WINDOW_SIZE, MAX_CELL_GAP, MAX_TICK_RATE,
+    MIN_GRID_DIMENSION, MIN_TICK_RATE, UI_HEIGHT,
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Number of columns on the board.
+    pub width: usize,
+    /// Number of rows on the board; 9/16 of the width when unset.
+    pub height: Option<usize>,
+    /// Initial cell size in pixels, which sizes the window to fit the whole board; a 1280x720 window when unset.
+    pub cell_size: Option<f32>,
+    /// Gap between neighboring cells as a fraction of the cell size, from 0 (no gaps) to 0.5.
+    pub cell_gap: f32,
+    /// Generations per second the simulation starts at.
+    pub tick_rate: u32,
+    /// Frames per second to draw at most; uncapped when unset.
+    pub max_fps: Option<u32>,
+    /// Cell colors as `[r, g, b]` with channels from 0 to 1. The `--gpu` simulation draws with the defaults.
+    pub alive_color: [f32; 3],
+    pub dead_color: [f32; 3],
+    /// First dying stage of a Generations rule; later stages fade towards `dead_color`.
+    pub dying_color: [f32; 3],
+    /// Window background, which also shows between cells when gaps are on.
+    pub background_color: [f32; 3],
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            width: DEFAULT_GRID_WIDTH,
+            height: None,
+            cell_size: None,
+            cell_gap: DEFAULT_CELL_GAP,
+            tick_rate: DEFAULT_TICK_RATE,
+            max_fps: None,
+            alive_color: Classic::DEFAULT.alive,
+            dead_color: Classic::DEFAULT.dead,
+            dying_color: Classic::DEFAULT.dying,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+        }
+    }
+}
+
+impl Config {
+    /// Read and validate the file at `path`.
+    pub fn load(path: &Path) -> anyhow::Result<Config> {
+        let text = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        let config: Config = toml::from_str(&text).with_context(|| format!("parse {}", path.display()))?;
+        config.validate().with_context(|| format!("invalid config {}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Check the values the type alone doesn't rule out, naming the key at fault.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (key, dimension) in [("width", Some(self.width)), ("height", self.height)] {
+            if let Some(dimension) = dimension.filter(|dimension| *dimension < MIN_GRID_DIMENSION) {
+                bail!("`{key}` must be at least {MIN_GRID_DIMENSION}, got {dimension}");
+            }
+        }
+        if let Some(cell_size) = self.cell_size.filter(|size| !size.is_finite() || *size <= 0.0) {
+            bail!("`cell_size` must be a positive number, got {cell_size}");
+        }
+        if !(0.0..=MAX_CELL_GAP).contains(&self.cell_gap) {
+            bail!("`cell_gap` must be between 0 and {MAX_CELL_GAP}, got {}", self.cell_gap);
+        }
+        if !(MIN_TICK_RATE..=MAX_TICK_RATE).contains(&self.tick_rate) {
+            bail!("`tick_rate` must be between {MIN_TICK_RATE} and {MAX_TICK_RATE}, got {}", self.tick_rate);
+        }
+        if self.max_fps == Some(0) {
+            bail!("`max_fps` must be at least 1");
+        }
+        for (key, color) in [
+            ("alive_color", self.alive_color),
+            ("dead_color", self.dead_color),
+            ("dying_color", self.dying_color),
+            ("background_color", self.background_color),
+        ] {
+            if !color.iter().all(|channel| (0.0..=1.0).contains(channel)) {
+                bail!("`{key}` channels must be between 0 and 1, got {color:?}");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn grid_size(&self) -> (usize, usize) {
+        let height = self.height.unwrap_or((self.width * 9 / 16).max(MIN_GRID_DIMENSION));
+        (self.width, height)
+    }
+
+    /// Window that fits `boards` boards side by side at `cell_size`.
+    pub fn window_size(&self, boards: usize) -> PhysicalSize<u32> {
+        let Some(cell_size) = self.cell_size else {
+            return DEFAULT_WINDOW_SIZE;
+        };
+        let (width, height) = self.grid_size();
+        PhysicalSize::new(
+            (boards as f32 * width as f32 * cell_size).ceil() as u32,
+            (height as f32 * cell_size + UI_HEIGHT).ceil() as u32,
+        )
+    }
+
+    pub fn color_scheme(&self) -> Classic {
+        Classic {
+            alive: self.alive_color,
+            dead: self.dead_color,
+            dying: self.dying_color,
+        }
+    }
+}
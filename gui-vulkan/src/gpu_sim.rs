@@ -0,0 +1,296 @@
+//! Game of Life stepped entirely on the GPU.
+//!
+//! Cells live in two storage buffers of `u32` (0 = dead, 1 = alive). Each step runs `cs_step` reading one
+//! buffer and writing the other, then the buffers swap roles. The grid is drawn straight from the current
+//! buffer by `vs_gpu_grid`, so nothing is uploaded per frame except the small parameter block.
+//!
+//! Only the classic Moore-neighborhood rule on a torus is implemented here.
+
+use std::sync::mpsc;
+
+use anyhow::Context;
+use bytemuck::{Pod, Zeroable};
+use shared::grid::{CellState, Grid};
+
+const WORKGROUP_SIZE: u32 = 8;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SimParams {
+    width: u32,
+    height: u32,
+    /// Top-left corner of the grid in normalized device coordinates.
+    origin: [f32; 2],
+    /// Size of one cell in normalized device coordinates (y measured downwards).
+    cell_extent: [f32; 2],
+    /// How far each cell is shrunk on every side, as a fraction of the cell size.
+    inset: f32,
+    _pad: f32,
+}
+
+pub struct GpuSimulation {
+    width: usize,
+    height: usize,
+    params_buffer: wgpu::Buffer,
+    cell_buffers: [wgpu::Buffer; 2],
+    compute_pipeline: wgpu::ComputePipeline,
+    /// `compute_bind_groups[i]` reads `cell_buffers[i]` and writes the other one.
+    compute_bind_groups: [wgpu::BindGroup; 2],
+    render_pipeline: wgpu::RenderPipeline,
+    render_bind_groups: [wgpu::BindGroup; 2],
+    current: usize,
+    pending_steps: u32,
+}
+
+impl GpuSimulation {
+    pub fn new(device: &wgpu::Device, shader: &wgpu::ShaderModule, surface_format: wgpu::TextureFormat, width: usize, height: usize) -> Self {
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sim_params_buffer"),
+            size: std::mem::size_of::<SimParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let cell_buffer = |label| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: (width * height * std::mem::size_of::<u32>()) as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            })
+        };
+        let cell_buffers = [cell_buffer("cell_buffer_a"), cell_buffer("cell_buffer_b")];
+
+        let compute_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sim_compute_layout"),
+            entries: &[
+                uniform_entry(0, wgpu::ShaderStages::COMPUTE),
+                storage_entry(1, wgpu::ShaderStages::COMPUTE, true),
+                storage_entry(2, wgpu::ShaderStages::COMPUTE, false),
+            ],
+        });
+        let render_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sim_render_layout"),
+            entries: &[
+                uniform_entry(0, wgpu::ShaderStages::VERTEX),
+                storage_entry(1, wgpu::ShaderStages::VERTEX, true),
+            ],
+        });
+
+        let compute_bind_groups = [0, 1].map(|src| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("sim_compute_bind_group"),
+                layout: &compute_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: params_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: cell_buffers[src].as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: cell_buffers[1 - src].as_entire_binding(),
+                    },
+                ],
+            })
+        });
+        let render_bind_groups = [0, 1].map(|index| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("sim_render_bind_group"),
+                layout: &render_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: params_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: cell_buffers[index].as_entire_binding(),
+                    },
+                ],
+            })
+        });
+
+        let compute_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("sim_compute_pipeline_layout"),
+            bind_group_layouts: &[&compute_layout],
+            push_constant_ranges: &[],
+        });
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("sim_compute_pipeline"),
+            layout: Some(&compute_pipeline_layout),
+            module: shader,
+            entry_point: Some("cs_step"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("sim_render_pipeline_layout"),
+            bind_group_layouts: &[&render_layout],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("sim_render_pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_gpu_grid"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 2]>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x2,
+                    }],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            width,
+            height,
+            params_buffer,
+            cell_buffers,
+            compute_pipeline,
+            compute_bind_groups,
+            render_pipeline,
+            render_bind_groups,
+            current: 0,
+            pending_steps: 0,
+        }
+    }
+
+    /// Replace the GPU state with the contents of `grid`.
+    pub fn upload(&mut self, queue: &wgpu::Queue, grid: &Grid) {
+        let cells: Vec<u32> = grid.cells.iter().flatten().map(|cell| u32::from(*cell == CellState::Alive)).collect();
+        queue.write_buffer(&self.cell_buffers[self.current], 0, bytemuck::cast_slice(&cells));
+    }
+
+    /// Copy the current GPU state back into `grid`, blocking until the copy has finished.
+    pub fn read_back(&self, device: &wgpu::Device, queue: &wgpu::Queue, grid: &mut Grid) -> anyhow::Result<()> {
+        let size = (self.width * self.height * std::mem::size_of::<u32>()) as u64;
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sim_readback_buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("sim_readback_encoder"),
+        });
+        encoder.copy_buffer_to_buffer(&self.cell_buffers[self.current], 0, &staging, 0, size);
+        queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::PollType::Wait).context("wait for readback")?;
+        receiver.recv().context("readback callback dropped")?.context("map readback buffer")?;
+
+        {
+            let data = slice.get_mapped_range();
+            let cells: &[u32] = bytemuck::cast_slice(&data);
+            for (index, value) in cells.iter().enumerate() {
+                let state = if *value != 0 { CellState::Alive } else { CellState::Dead };
+                grid.set(index / self.width, index % self.width, state);
+            }
+        }
+        staging.unmap();
+        Ok(())
+    }
+
+    /// Position the grid on screen; `origin` and `cell_extent` are in normalized device coordinates and `inset` is
+    /// the fraction of a cell left empty on each side.
+    pub fn set_viewport(&self, queue: &wgpu::Queue, origin: [f32; 2], cell_extent: [f32; 2], inset: f32) {
+        let params = SimParams {
+            width: self.width as u32,
+            height: self.height as u32,
+            origin,
+            cell_extent,
+            inset,
+            _pad: 0.0,
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+    }
+
+    /// Schedule `steps` generations to run with the next [`encode_steps`](Self::encode_steps).
+    pub fn queue_steps(&mut self, steps: u32) {
+        self.pending_steps += steps;
+    }
+
+    pub fn encode_steps(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if self.pending_steps == 0 {
+            return;
+        }
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("sim_compute_pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.compute_pipeline);
+        let groups_x = (self.width as u32).div_ceil(WORKGROUP_SIZE);
+        let groups_y = (self.height as u32).div_ceil(WORKGROUP_SIZE);
+        for _ in 0..self.pending_steps {
+            pass.set_bind_group(0, &self.compute_bind_groups[self.current], &[]);
+            pass.dispatch_workgroups(groups_x, groups_y, 1);
+            self.current = 1 - self.current;
+        }
+        self.pending_steps = 0;
+    }
+
+    /// Draw the current generation using the shared unit-quad vertex buffer.
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass<'_>, quad: &wgpu::Buffer, quad_vertex_count: u32) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.render_bind_groups[self.current], &[]);
+        render_pass.set_vertex_buffer(0, quad.slice(..));
+        render_pass.draw(0..quad_vertex_count, 0..(self.width * self.height) as u32);
+    }
+}
+
+fn uniform_entry(binding: u32, visibility: wgpu::ShaderStages) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+fn storage_entry(binding: u32, visibility: wgpu::ShaderStages, read_only: bool) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
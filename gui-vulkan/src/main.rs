@@ -1,21 +1,45 @@
+mod config;
+mod font;
+mod gpu_sim;
+mod screenshot;
+
+use std::fs;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use bytemuck::{Pod, Zeroable};
-use shared::grid::{CellState, Grid};
+use clap::Parser;
+use config::Config;
+use font::{glyph_bits, FONT_HEIGHT, FONT_WIDTH};
+use gpu_sim::GpuSimulation;
+use shared::colors::{mix, Classic, ColorScheme, Heatmap};
+use shared::grid::{EdgeMode, Grid, Neighborhood, Rule};
+use shared::patterns;
 use wgpu::util::DeviceExt;
 use wgpu::StoreOp;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
-use winit::event::{ElementState, KeyEvent, MouseButton, WindowEvent};
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::keyboard::{Key, NamedKey};
 use winit::window::{Window, WindowAttributes, WindowId};
 
-const GRID_WIDTH: usize = 200;
-const GRID_HEIGHT: usize = GRID_WIDTH * 9 / 16;
-const STEP_INTERVAL: Duration = Duration::from_millis(0);
+const DEFAULT_GRID_WIDTH: usize = 200;
+/// Smallest board edge accepted on the command line; the neighbor counting assumes at least 3x3.
+const MIN_GRID_DIMENSION: usize = 3;
+const DEFAULT_WINDOW_SIZE: PhysicalSize<u32> = PhysicalSize::new(1280, 720);
+/// Generations per second at startup.
+const DEFAULT_TICK_RATE: u32 = 60;
+const MIN_TICK_RATE: u32 = 1;
+const MAX_TICK_RATE: u32 = 1920;
+/// Tick rates `+` and `-` step through, slowest first.
+const SPEED_PRESETS: [u32; 5] = [1, 5, 10, 30, 60];
+/// Upper bound on catch-up steps in one frame; a backlog beyond this is dropped so slow frames can't snowball.
+const MAX_STEPS_PER_FRAME: u32 = 64;
 const UI_HEIGHT: f32 = 90.0;
 const BUTTON_WIDTH: f32 = 180.0;
 const BUTTON_HEIGHT: f32 = 44.0;
@@ -23,15 +47,218 @@ const BUTTON_PADDING: f32 = 24.0;
 const BUTTON_VERTICAL_OFFSET: f32 = 12.0;
 const TEXT_SCALE_HEADING: f32 = 10.0;
 const TEXT_SCALE_BUTTON: f32 = 8.0;
+const TEXT_SCALE_STATS: f32 = 3.0;
+/// Weight given to the newest per-second FPS sample; older samples decay geometrically.
+const FPS_SMOOTHING: f64 = 0.5;
+/// Shortest time between two warnings about frames dropped because of surface errors.
+const SKIPPED_FRAME_WARNING_INTERVAL: Duration = Duration::from_secs(5);
 const GRID_BASE_VERTEX_COUNT: u32 = 6;
-const FONT_WIDTH: usize = 5;
-const FONT_HEIGHT: usize = 7;
+const MIN_ZOOM: f32 = 1.0;
+const MAX_ZOOM: f32 = 32.0;
+/// Zoom factor applied per mouse-wheel line.
+const ZOOM_STEP: f32 = 1.15;
+/// Pixels of touchpad scrolling treated as one wheel line.
+const PIXELS_PER_SCROLL_LINE: f32 = 40.0;
+/// How much of the grid must stay on screen while panning, in pixels.
+const PAN_MARGIN: f32 = 48.0;
+/// Grid lines are skipped below this cell size, where they would cover most of the cells.
+const GRID_LINE_MIN_CELL_SIZE: f32 = 4.0;
+const GRID_LINE_WIDTH: f32 = 1.0;
+const GRID_LINE_COLOR: [f32; 3] = [0.08, 0.08, 0.1];
+/// Space left between neighboring cells, as a fraction of the cell size, when cell gaps are on.
+const DEFAULT_CELL_GAP: f32 = 0.1;
+const MAX_CELL_GAP: f32 = 0.5;
+const CELL_GAP_STEP: f32 = 0.05;
+/// Outline thickness of the keyboard cursor, in pixels; thinner on cells too small to fit it.
+const KEY_CURSOR_WIDTH: f32 = 2.0;
+const KEY_CURSOR_COLOR: [f32; 3] = [1.0, 0.75, 0.2];
+/// Empty cells between the demo glider gun and the top-left corner of the board.
+const DEMO_GUN_MARGIN: usize = 1;
+/// Controls listed by the help overlay, in the order shown.
+const SHORTCUTS: [(&str, &str); 25] = [
+    ("H / ?", "Show or hide this help"),
+    ("R", "Randomize"),
+    ("Space", "Pause or resume"),
+    ("S", "Step while paused"),
+    ("[ / ]", "Slower / faster"),
+    ("- / +", "Previous / next speed preset"),
+    ("Arrows", "Move the keyboard cursor"),
+    ("Enter", "Toggle the cell under the cursor"),
+    ("Esc", "Hide the keyboard cursor"),
+    ("C", "Age heatmap"),
+    ("G", "Grid lines"),
+    ("B", "Gaps between cells"),
+    (", / .", "Narrower / wider gaps"),
+    ("W", "Wrapping or dead edges"),
+    ("N", "Moore, Von Neumann or hex neighbors"),
+    ("V", "High contrast"),
+    ("1", "Glider gun demo"),
+    ("P", "Screenshot"),
+    ("M", "Minimap"),
+    ("F", "Next present mode"),
+    ("K", "Next background color"),
+    ("Click", "Toggle a cell"),
+    ("Wheel", "Zoom"),
+    ("Middle drag", "Pan"),
+    ("Drop a file", "Load a pattern"),
+];
+/// Largest text scale of the help overlay; it shrinks on boards too small for it.
+const HELP_TEXT_SCALE: f32 = 3.0;
+/// Opacity of the help overlay's backdrop, low enough to keep the board visible underneath.
+const HELP_BACKDROP_ALPHA: f32 = 0.85;
+/// Longest side of the minimap, in pixels.
+const MINIMAP_SIZE: f32 = 160.0;
+/// Most blocks the minimap draws along its longest side; on larger grids each block aggregates several cells.
+const MINIMAP_MAX_BLOCKS: usize = 80;
+/// Distance between the minimap and the corner of the board area.
+const MINIMAP_MARGIN: f32 = 12.0;
+const MINIMAP_BACKDROP_ALPHA: f32 = 0.85;
+const MINIMAP_VIEWPORT_COLOR: [f32; 3] = [1.0, 0.75, 0.2];
+/// Window background behind the boards and between cells, unless the config sets another.
+const DEFAULT_BACKGROUND_COLOR: [f32; 3] = [0.05, 0.05, 0.07];
+/// Backgrounds `K` cycles through after the configured one: black for dark themes, white for printing.
+const BACKGROUND_PRESETS: [[f32; 3]; 2] = [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]];
+/// Present modes `F` cycles through, skipping those the surface doesn't support. Mailbox and Immediate don't wait
+/// for vertical sync; Fifo does and is always available.
+const PRESENT_MODES: [wgpu::PresentMode; 4] = [
+    wgpu::PresentMode::Mailbox,
+    wgpu::PresentMode::Immediate,
+    wgpu::PresentMode::Fifo,
+    wgpu::PresentMode::FifoRelaxed,
+];
+/// How long a notice such as a pattern load error stays on screen.
+const NOTICE_DURATION: Duration = Duration::from_secs(4);
+/// Shown in place of a notice while the board doesn't fit the window even at one pixel per cell.
+const OVERFLOW_WARNING: &str = "Board larger than window - middle-drag to pan, M for minimap";
+
+/// Colors of the UI bar and its text.
+#[derive(Clone, Copy)]
+struct UiPalette {
+    bar: [f32; 3],
+    header_line: [f32; 3],
+    button: [f32; 3],
+    button_hovered: [f32; 3],
+    heading: [f32; 3],
+    button_text: [f32; 3],
+    stats: [f32; 3],
+    notice: [f32; 3],
+    /// How far each glyph pixel grows on every side, as a fraction of the text scale; thickens the strokes.
+    glyph_bleed: f32,
+}
+
+impl UiPalette {
+    const DEFAULT: UiPalette = UiPalette {
+        bar: [0.05, 0.05, 0.07],
+        header_line: [0.15, 0.15, 0.2],
+        button: [0.25, 0.33, 0.55],
+        button_hovered: [0.35, 0.45, 0.75],
+        heading: [0.9, 0.9, 0.95],
+        button_text: [0.95, 0.95, 0.98],
+        stats: [0.75, 0.78, 0.85],
+        notice: [0.95, 0.8, 0.45],
+        glyph_bleed: 0.0,
+    };
+
+    /// Pure black and white with bolder glyphs, for readers who find the gray-on-dark text too faint.
+    const HIGH_CONTRAST: UiPalette = UiPalette {
+        bar: [0.0, 0.0, 0.0],
+        header_line: [1.0, 1.0, 1.0],
+        button: [1.0, 1.0, 1.0],
+        button_hovered: [0.8, 0.8, 0.8],
+        heading: [1.0, 1.0, 1.0],
+        button_text: [0.0, 0.0, 0.0],
+        stats: [1.0, 1.0, 1.0],
+        notice: [1.0, 1.0, 1.0],
+        glyph_bleed: 0.15,
+    };
+}
+
+/// Conway's Game of Life rendered with wgpu.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// TOML file with the board size, simulation speed and cell colors; the flags below take precedence
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Number of columns on the board [default: 200]
+    #[arg(long, value_parser = parse_dimension)]
+    width: Option<usize>,
+
+    /// Number of rows on the board [default: 9/16 of the width]
+    #[arg(long, value_parser = parse_dimension)]
+    height: Option<usize>,
+
+    /// Initial cell size in pixels; sizes the window to fit the whole board [default: fit a 1280x720 window]
+    #[arg(long, value_parser = parse_cell_size)]
+    cell_size: Option<f32>,
+
+    /// Step the simulation in a compute shader instead of on the CPU; supports only B3/S23
+    #[arg(long)]
+    gpu: bool,
+
+    /// Rule in B/S notation, with `/C<states>` for Generations rules such as Brian's Brain (B2/S/C3)
+    #[arg(long, default_value_t = Rule::CONWAY)]
+    rule: Rule,
+
+    /// Run a second board under this rule next to the first, both starting from the same random cells
+    #[arg(long)]
+    compare: Option<Rule>,
+
+    /// Graphics API to use; falls back to any available backend when it has no usable adapter
+    #[arg(long, value_enum, default_value_t = BackendChoice::Auto)]
+    backend: BackendChoice,
+
+    /// Draw at most this many frames per second, sleeping in between [default: as fast as the present mode allows]
+    #[arg(long, value_parser = parse_max_fps)]
+    max_fps: Option<u32>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum BackendChoice {
+    /// The platform's primary API: Vulkan, Metal or DirectX 12
+    Auto,
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+}
+
+impl BackendChoice {
+    fn backends(self) -> wgpu::Backends {
+        match self {
+            BackendChoice::Auto => wgpu::Backends::PRIMARY,
+            BackendChoice::Vulkan => wgpu::Backends::VULKAN,
+            BackendChoice::Metal => wgpu::Backends::METAL,
+            BackendChoice::Dx12 => wgpu::Backends::DX12,
+            BackendChoice::Gl => wgpu::Backends::GL,
+        }
+    }
+}
+
+impl Args {
+    /// The `--config` file, or the defaults without one, with the size flags given on the command line applied.
+    fn config(&self) -> anyhow::Result<Config> {
+        let mut config = self.config.as_deref().map(Config::load).transpose()?.unwrap_or_default();
+        config.width = self.width.unwrap_or(config.width);
+        config.height = self.height.or(config.height);
+        config.cell_size = self.cell_size.or(config.cell_size);
+        config.max_fps = self.max_fps.or(config.max_fps);
+        Ok(config)
+    }
+
+    /// Rule of every board, left to right.
+    fn rules(&self) -> Vec<Rule> {
+        std::iter::once(self.rule).chain(self.compare).collect()
+    }
+}
 
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 struct Vertex {
     position: [f32; 2],
     color: [f32; 3],
+    alpha: f32,
 }
 
 #[repr(C)]
@@ -55,6 +282,57 @@ impl Rect {
     }
 }
 
+/// Where the grid sits inside the window, in physical pixels.
+#[derive(Copy, Clone)]
+struct GridLayout {
+    offset: [f32; 2],
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    /// The board needs cells smaller than a pixel to fit the grid area, so `cell_size` was raised to one pixel and
+    /// part of the board lies outside the window even when zoomed all the way out.
+    overflows: bool,
+    /// Odd rows are drawn half a cell to the right, for the hex neighborhood.
+    hex: bool,
+}
+
+impl GridLayout {
+    fn rect(&self) -> Rect {
+        let hex_width = if self.hex && self.rows > 1 { self.cell_size * 0.5 } else { 0.0 };
+        Rect {
+            min: self.offset,
+            max: [
+                self.offset[0] + self.cell_size * self.columns as f32 + hex_width,
+                self.offset[1] + self.cell_size * self.rows as f32,
+            ],
+        }
+    }
+
+    /// The `(row, col)` under `point`, or `None` when the point is outside the grid.
+    fn cell_at(&self, point: [f32; 2]) -> Option<(usize, usize)> {
+        let rect = self.rect();
+        if point[0] < rect.min[0] || point[1] < rect.min[1] || point[0] >= rect.max[0] || point[1] >= rect.max[1] {
+            return None;
+        }
+        let row = ((point[1] - self.offset[1]) / self.cell_size) as usize;
+        let x = point[0] - self.offset[0] - self.row_shift(row);
+        if x < 0.0 {
+            return None;
+        }
+        let col = (x / self.cell_size) as usize;
+        (row < self.rows && col < self.columns).then_some((row, col))
+    }
+
+    /// How far right `row` is drawn: half a cell for odd rows of a hex board, nothing otherwise.
+    fn row_shift(&self, row: usize) -> f32 {
+        if self.hex && !row.is_multiple_of(2) {
+            self.cell_size * 0.5
+        } else {
+            0.0
+        }
+    }
+}
+
 struct State {
     #[allow(dead_code)]
     instance: wgpu::Instance,
@@ -62,6 +340,8 @@ struct State {
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
+    /// Entries of [`PRESENT_MODES`] the surface supports, in the same order.
+    present_modes: Vec<wgpu::PresentMode>,
     size: PhysicalSize<u32>,
     grid_pipeline: wgpu::RenderPipeline,
     ui_pipeline: wgpu::RenderPipeline,
@@ -70,26 +350,42 @@ struct State {
     grid_instance_capacity: usize,
     ui_vertex_buffer: wgpu::Buffer,
     ui_vertex_capacity: usize,
+    /// Present when the simulation runs in a compute shader instead of on the CPU.
+    gpu_sim: Option<GpuSimulation>,
+    /// Graphics API the adapter was actually created with.
+    backend: wgpu::Backend,
+    /// Color the frame is cleared to before the boards are drawn.
+    clear_color: [f32; 3],
+    /// Save the next rendered frame as a PNG.
+    screenshot_requested: bool,
+    /// Frames dropped because of surface errors since the last warning about them.
+    skipped_frames: u32,
+    last_skip_warning: Option<Instant>,
 }
 
 impl State {
-    async fn new(window: Arc<Window>) -> anyhow::Result<Self> {
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::VULKAN,
-            flags: wgpu::InstanceFlags::from_env_or_default(),
-            backend_options: wgpu::BackendOptions::default(),
-        });
-
-        let surface = instance.create_surface(window.clone()).context("create surface")?;
-
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .context("request adapter")?;
+    async fn new(window: Arc<Window>, args: &Args, config: &Config) -> anyhow::Result<Self> {
+        let (grid_width, grid_height) = config.grid_size();
+        let clear_color = config.background_color;
+        let (instance, surface, adapter) = match request_adapter(&window, args.backend.backends()).await {
+            Ok(found) => found,
+            Err(err) => {
+                log::warn!("{:?} backend unavailable ({err:#}), trying every backend", args.backend);
+                request_adapter(&window, wgpu::Backends::all()).await?
+            }
+        };
+        let info = adapter.get_info();
+        let backend = info.backend;
+        log::info!(
+            "using {backend:?} adapter {} ({:?}, driver: {} {})",
+            info.name,
+            info.device_type,
+            info.driver,
+            info.driver_info
+        );
+        if info.device_type == wgpu::DeviceType::Cpu {
+            log::warn!("{} renders in software; expect low frame rates", info.name);
+        }
 
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
@@ -109,23 +405,19 @@ impl State {
             .copied()
             .find(|format| format.is_srgb())
             .unwrap_or(capabilities.formats[0]);
-        let present_mode = capabilities
-            .present_modes
-            .iter()
-            .copied()
-            .find(|mode| matches!(mode, wgpu::PresentMode::Mailbox))
-            .or_else(|| {
-                capabilities
-                    .present_modes
-                    .iter()
-                    .copied()
-                    .find(|mode| matches!(mode, wgpu::PresentMode::Immediate))
-            })
-            .unwrap_or(wgpu::PresentMode::Fifo);
+        let present_modes: Vec<_> = PRESENT_MODES
+            .into_iter()
+            .filter(|mode| *mode == wgpu::PresentMode::Fifo || capabilities.present_modes.contains(mode))
+            .collect();
+        // The first supported mode is the fastest one; Fifo is the last resort.
+        let present_mode = present_modes[0];
+        log::info!("surface format: {surface_format:?}, present mode: {present_mode:?}");
 
         let size = window.inner_size();
+        // Screenshots copy the frame out of the surface texture, which not every surface allows.
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT | (capabilities.usages & wgpu::TextureUsages::COPY_SRC);
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: surface_format,
             width: size.width.max(1),
             height: size.height.max(1),
@@ -159,7 +451,7 @@ impl State {
             usage: wgpu::BufferUsages::VERTEX,
         });
 
-        let grid_instance_capacity = GRID_WIDTH * GRID_HEIGHT;
+        let grid_instance_capacity = grid_width * grid_height;
         let grid_instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("grid_instance_buffer"),
             size: (grid_instance_capacity * std::mem::size_of::<CellInstance>()) as u64,
@@ -253,6 +545,11 @@ impl State {
                             shader_location: 1,
                             format: wgpu::VertexFormat::Float32x3,
                         },
+                        wgpu::VertexAttribute {
+                            offset: 20,
+                            shader_location: 2,
+                            format: wgpu::VertexFormat::Float32,
+                        },
                     ],
                 }],
             },
@@ -273,12 +570,17 @@ impl State {
             cache: None,
         });
 
+        let gpu_sim = args
+            .gpu
+            .then(|| GpuSimulation::new(&device, &shader, surface_format, grid_width, grid_height));
+
         Ok(Self {
             instance,
             surface,
             device,
             queue,
             config,
+            present_modes,
             size,
             grid_pipeline,
             ui_pipeline,
@@ -287,6 +589,12 @@ impl State {
             grid_instance_capacity,
             ui_vertex_buffer,
             ui_vertex_capacity,
+            gpu_sim,
+            backend,
+            clear_color,
+            screenshot_requested: false,
+            skipped_frames: 0,
+            last_skip_warning: None,
         })
     }
 
@@ -300,6 +608,33 @@ impl State {
         self.surface.configure(&self.device, &self.config);
     }
 
+    /// Deal with a frame `render` could not acquire. The frame is dropped rather than re-acquired on the spot, so a
+    /// surface that keeps failing costs one attempt per frame. Returns `false` when rendering cannot go on.
+    fn recover_from(&mut self, err: wgpu::SurfaceError) -> bool {
+        match err {
+            // The swapchain no longer matches the window or is gone altogether; rebuild it for the next frame.
+            wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost => self.surface.configure(&self.device, &self.config),
+            // The compositor didn't hand out a texture in time, or the backend reported the details elsewhere.
+            wgpu::SurfaceError::Timeout | wgpu::SurfaceError::Other => {}
+            wgpu::SurfaceError::OutOfMemory => {
+                log::error!("stopping: {err}");
+                return false;
+            }
+        }
+
+        // Transient errors can hit every frame for a while, e.g. during a resize, so summarize them.
+        self.skipped_frames += 1;
+        if self
+            .last_skip_warning
+            .is_none_or(|warned| warned.elapsed() >= SKIPPED_FRAME_WARNING_INTERVAL)
+        {
+            log::warn!("skipped {} frame(s), most recently because: {err}", self.skipped_frames);
+            self.skipped_frames = 0;
+            self.last_skip_warning = Some(Instant::now());
+        }
+        true
+    }
+
     fn ensure_grid_instance_capacity(&mut self, required_instances: usize) {
         if required_instances <= self.grid_instance_capacity {
             return;
@@ -327,19 +662,7 @@ impl State {
     }
 
     fn render(&mut self, instances: &[CellInstance], ui_vertices: &[Vertex]) -> std::result::Result<(), wgpu::SurfaceError> {
-        let frame = match self.surface.get_current_texture() {
-            Ok(frame) => frame,
-            Err(err) => {
-                match err {
-                    wgpu::SurfaceError::Lost => {
-                        self.surface.configure(&self.device, &self.config);
-                    }
-                    wgpu::SurfaceError::OutOfMemory => return Err(err),
-                    _ => {}
-                }
-                self.surface.get_current_texture()?
-            }
-        };
+        let frame = self.surface.get_current_texture()?;
 
         if !instances.is_empty() {
             self.ensure_grid_instance_capacity(instances.len());
@@ -358,6 +681,10 @@ impl State {
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("encoder") });
 
+        if let Some(gpu_sim) = self.gpu_sim.as_mut() {
+            gpu_sim.encode_steps(&mut encoder);
+        }
+
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("render_pass"),
@@ -366,9 +693,9 @@ impl State {
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.05,
-                            g: 0.05,
-                            b: 0.07,
+                            r: f64::from(self.clear_color[0]),
+                            g: f64::from(self.clear_color[1]),
+                            b: f64::from(self.clear_color[2]),
                             a: 1.0,
                         }),
                         store: StoreOp::Store,
@@ -379,7 +706,9 @@ impl State {
                 timestamp_writes: None,
             });
 
-            if !instances.is_empty() {
+            if let Some(gpu_sim) = &self.gpu_sim {
+                gpu_sim.draw(&mut render_pass, &self.grid_vertex_buffer, GRID_BASE_VERTEX_COUNT);
+            } else if !instances.is_empty() {
                 render_pass.set_pipeline(&self.grid_pipeline);
                 render_pass.set_vertex_buffer(0, self.grid_vertex_buffer.slice(..));
                 let instance_bytes = std::mem::size_of_val(instances) as u64;
@@ -395,60 +724,582 @@ impl State {
             }
         }
 
+        let screenshot = std::mem::take(&mut self.screenshot_requested)
+            .then(|| screenshot::encode_copy(&self.device, &mut encoder, &frame.texture))
+            .and_then(|pending| pending.inspect_err(|err| log::error!("screenshot failed: {err:#}")).ok());
+
         self.queue.submit(Some(encoder.finish()));
         frame.present();
+
+        if let Some(pending) = screenshot {
+            let path = screenshot::timestamped_path();
+            match pending.save(&self.device, &path) {
+                Ok(()) => log::info!("saved screenshot to {}", path.display()),
+                Err(err) => log::error!("screenshot failed: {err:#}"),
+            }
+        }
         Ok(())
     }
+
+    fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    /// Switch to the next supported present mode, taking effect from the next frame.
+    fn cycle_present_mode(&mut self) {
+        let current = self.present_modes.iter().position(|mode| *mode == self.config.present_mode);
+        let next = current.map_or(0, |index| (index + 1) % self.present_modes.len());
+        self.config.present_mode = self.present_modes[next];
+        self.surface.configure(&self.device, &self.config);
+        log::info!("present mode: {:?}", self.config.present_mode);
+    }
+}
+
+/// Create an instance limited to `backends` and find an adapter that can present to `window`.
+async fn request_adapter(window: &Arc<Window>, backends: wgpu::Backends) -> anyhow::Result<(wgpu::Instance, wgpu::Surface<'static>, wgpu::Adapter)> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends,
+        flags: wgpu::InstanceFlags::from_env_or_default(),
+        backend_options: wgpu::BackendOptions::default(),
+    });
+
+    let surface = instance.create_surface(window.clone()).context("create surface")?;
+
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: Some(&surface),
+            force_fallback_adapter: false,
+        })
+        .await
+        .context("request adapter")?;
+    Ok((instance, surface, adapter))
 }
 
+/// Extra overlay geometry for a board, called with its grid, the UI vertex list and the window size in pixels. Build
+/// on [`push_rect`] and [`push_translucent_rect`], which take pixel coordinates and convert them with [`to_ndc`] and
+/// [`to_ndc_y`].
+type FrameHook = Box<dyn FnMut(&Grid, &mut Vec<Vertex>, [f32; 2])>;
+
 struct GameOfLifeApp {
     grid: Grid,
-    last_step: Instant,
+    grid_width: usize,
+    grid_height: usize,
+    /// Step the simulation on the GPU; `grid` is then only synced on edits.
+    gpu: bool,
+    /// Generations computed on the GPU since `grid` was last randomized.
+    gpu_generations: u64,
+    gpu_pending_steps: u32,
+    /// `grid` was edited on the CPU and must be uploaded before the next GPU step.
+    gpu_upload_pending: bool,
+    paused: bool,
+    /// The last generation changed nothing, so frames are only drawn on input until an edit wakes the board up.
+    settled: bool,
+    /// Simulation speed in generations per second, independent of the frame rate.
+    tick_rate: u32,
+    /// Colors of the cells, with the heatmap taking over alive cells while it is on.
+    scheme: Classic,
+    /// Simulated time not yet turned into generations.
+    tick_accumulator: Duration,
+    last_update: Instant,
     window_size: PhysicalSize<u32>,
+    /// Part of the window this board and its UI bar are drawn in; the whole window unless comparing rules.
+    viewport: Rect,
+    /// Heading of the UI bar.
+    title: String,
+    /// Keep the rule when placing patterns, so a side-by-side comparison stays one.
+    rule_locked: bool,
     cursor_position: Option<[f32; 2]>,
+    /// Magnification relative to the fit-to-window layout.
+    zoom: f32,
+    /// Offset of the grid center from the center of the grid area, in pixels.
+    pan: [f32; 2],
+    /// Cursor position at the last pan update while the middle button is held.
+    pan_anchor: Option<[f32; 2]>,
+    /// Color alive cells by age instead of flat white.
+    heatmap: bool,
+    /// Outline every cell when they are large enough to tell apart.
+    grid_lines: bool,
+    /// Shrink every cell by `cell_gap` so the background shows between them.
+    cell_gaps: bool,
+    /// Gap between neighboring cells as a fraction of the cell size, kept while gaps are off.
+    cell_gap: f32,
+    /// `(row, col)` of the cell edited from the keyboard; hidden until an arrow key is pressed.
+    key_cursor: Option<(usize, usize)>,
+    /// Smoothed frames per second, `None` until the first sample is in.
+    fps: Option<f64>,
+    /// Black-and-white UI with bold text instead of the default grays.
+    high_contrast: bool,
+    /// Draw the list of controls over the board.
+    show_help: bool,
+    /// Draw an overview of the whole grid in the bottom-right corner.
+    show_minimap: bool,
+    /// Message shown below the UI bar and when it was posted.
+    notice: Option<(String, Instant)>,
+    /// Runs at the end of every `build_frame`, so its vertices are drawn over the built-in UI. The grid is stale
+    /// while the GPU simulation runs.
+    on_build_frame: Option<FrameHook>,
     instances: Vec<CellInstance>,
     ui_vertices: Vec<Vertex>,
 }
 
 impl GameOfLifeApp {
-    fn new(window_size: PhysicalSize<u32>) -> Self {
+    fn new(window_size: PhysicalSize<u32>, config: &Config, rule: Rule, gpu: bool) -> Self {
+        let (grid_width, grid_height) = config.grid_size();
+        let mut grid = Grid::new(grid_width, grid_height);
+        grid.set_rule(rule);
         Self {
-            grid: Grid::new(GRID_WIDTH, GRID_HEIGHT),
-            last_step: Instant::now(),
+            grid,
+            grid_width,
+            grid_height,
+            gpu,
+            gpu_generations: 0,
+            gpu_pending_steps: 0,
+            gpu_upload_pending: gpu,
+            paused: false,
+            settled: false,
+            tick_rate: config.tick_rate,
+            scheme: config.color_scheme(),
+            tick_accumulator: Duration::ZERO,
+            last_update: Instant::now(),
             window_size,
+            viewport: Rect {
+                min: [0.0, 0.0],
+                max: [window_size.width as f32, window_size.height as f32],
+            },
+            title: "Game of Life".to_string(),
+            rule_locked: false,
             cursor_position: None,
-            instances: Vec::with_capacity(GRID_WIDTH * GRID_HEIGHT),
+            zoom: MIN_ZOOM,
+            pan: [0.0, 0.0],
+            pan_anchor: None,
+            heatmap: false,
+            grid_lines: false,
+            cell_gaps: config.cell_gap > 0.0,
+            cell_gap: if config.cell_gap > 0.0 { config.cell_gap } else { DEFAULT_CELL_GAP },
+            key_cursor: None,
+            fps: None,
+            high_contrast: false,
+            show_help: false,
+            show_minimap: false,
+            notice: None,
+            on_build_frame: None,
+            instances: Vec::with_capacity(grid_width * grid_height),
             ui_vertices: Vec::with_capacity(2048),
         }
     }
 
-    fn resize(&mut self, size: PhysicalSize<u32>) {
+    /// One of several boards next to each other: headed by its rule, which patterns don't replace.
+    fn compared(mut self) -> Self {
+        self.title = self.grid.rule().to_string();
+        self.rule_locked = true;
+        self
+    }
+
+    fn resize(&mut self, size: PhysicalSize<u32>, viewport: Rect) {
         self.window_size = size;
+        self.viewport = viewport;
+        self.clamp_pan();
+    }
+
+    /// Zoom by `lines` wheel notches, keeping the grid point under `focus` fixed on screen.
+    fn zoom_at(&mut self, focus: [f32; 2], lines: f32) {
+        let before = self.grid_layout();
+        self.zoom = (self.zoom * ZOOM_STEP.powf(lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan = [0.0, 0.0];
+        let centered = self.grid_layout();
+
+        let scale = centered.cell_size / before.cell_size;
+        self.pan = std::array::from_fn(|axis| {
+            let anchored_offset = focus[axis] - (focus[axis] - before.offset[axis]) * scale;
+            anchored_offset - centered.offset[axis]
+        });
+        self.clamp_pan();
     }
 
+    fn begin_pan(&mut self, position: [f32; 2]) {
+        self.pan_anchor = Some(position);
+    }
+
+    fn end_pan(&mut self) {
+        self.pan_anchor = None;
+    }
+
+    fn drag_to(&mut self, position: [f32; 2]) {
+        if let Some(anchor) = self.pan_anchor.replace(position) {
+            self.pan[0] += position[0] - anchor[0];
+            self.pan[1] += position[1] - anchor[1];
+            self.clamp_pan();
+        }
+    }
+
+    /// Keep at least `PAN_MARGIN` pixels of the grid inside the grid area.
+    fn clamp_pan(&mut self) {
+        let width = (self.viewport.max[0] - self.viewport.min[0]).max(1.0);
+        let usable_height = (self.window_size.height.max(1) as f32 - UI_HEIGHT).max(1.0);
+        let cell_size = self.grid_layout().cell_size;
+        let grid_size = [cell_size * self.grid_width as f32, cell_size * self.grid_height as f32];
+        let area = [width, usable_height];
+        for axis in 0..2 {
+            let limit = ((grid_size[axis] + area[axis]) * 0.5 - PAN_MARGIN.min(grid_size[axis])).max(0.0);
+            self.pan[axis] = self.pan[axis].clamp(-limit, limit);
+        }
+    }
+
+    /// Run as many generations as the time since the last frame is worth at the current tick rate.
     fn update(&mut self) {
-        if self.last_step.elapsed() >= STEP_INTERVAL {
-            self.grid.advance();
-            self.last_step = Instant::now();
+        let now = Instant::now();
+        let elapsed = now - self.last_update;
+        self.last_update = now;
+        if self.paused {
+            return;
+        }
+        if self.settled {
+            // Time spent idle isn't owed generations; just check whether an edit brought the board back to life.
+            self.settled = !self.advance();
+            self.tick_accumulator = Duration::ZERO;
+            return;
+        }
+
+        let tick = Duration::from_secs(1) / self.tick_rate;
+        self.tick_accumulator += elapsed;
+        let mut steps = 0;
+        while self.tick_accumulator >= tick {
+            if steps == MAX_STEPS_PER_FRAME {
+                self.tick_accumulator = Duration::ZERO;
+                break;
+            }
+            if !self.advance() {
+                self.settled = true;
+                self.tick_accumulator = Duration::ZERO;
+                break;
+            }
+            self.tick_accumulator -= tick;
+            steps += 1;
         }
     }
 
-    fn randomize(&mut self) {
-        self.grid.randomize();
-        self.last_step = Instant::now();
+    fn faster(&mut self) {
+        self.tick_rate = (self.tick_rate * 2).min(MAX_TICK_RATE);
+    }
+
+    fn slower(&mut self) {
+        self.tick_rate = (self.tick_rate / 2).max(MIN_TICK_RATE);
+    }
+
+    /// Switch to the closest speed preset above (or below) the current tick rate, staying put past the last one, and
+    /// show the rate either way.
+    fn step_speed_preset(&mut self, faster: bool) {
+        let preset = if faster {
+            SPEED_PRESETS.into_iter().find(|&rate| rate > self.tick_rate)
+        } else {
+            SPEED_PRESETS.into_iter().rev().find(|&rate| rate < self.tick_rate)
+        };
+        self.tick_rate = preset.unwrap_or(self.tick_rate);
+        self.show_notice(format!("Speed: {} generations/s", self.tick_rate));
+    }
+
+    /// Returns whether the board may have changed; GPU steps always count as a change since the CPU can't tell.
+    fn advance(&mut self) -> bool {
+        if self.gpu {
+            self.gpu_pending_steps += 1;
+            self.gpu_generations += 1;
+            true
+        } else {
+            self.grid.advance()
+        }
+    }
+
+    /// Whether the next frame may look different without any input: the board is running and still changing.
+    fn is_animating(&self) -> bool {
+        !self.paused && !self.settled
+    }
+
+    /// When the notice below the UI bar is due to disappear.
+    fn notice_expiry(&self) -> Option<Instant> {
+        self.notice.as_ref().map(|(_, posted)| *posted + NOTICE_DURATION)
+    }
+
+    fn generation(&self) -> u64 {
+        self.grid.generation() + self.gpu_generations
+    }
+
+    /// Fill the board from `seed`, so boards of the same size started from one seed look alike.
+    fn randomize_seeded(&mut self, seed: u64) {
+        self.grid.randomize_seeded(seed);
+        self.restart();
+    }
+
+    /// Replace the board with the pattern in `path`, centered. Failures are logged and shown on screen.
+    fn load_pattern(&mut self, path: &Path) {
+        let name = path
+            .file_name()
+            .map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned());
+        match read_pattern(path).and_then(|pattern| self.place_pattern(&pattern)) {
+            Ok(()) => {
+                log::info!("loaded {}", path.display());
+                self.show_notice(format!("Loaded {name}"));
+            }
+            Err(err) => {
+                log::error!("failed to load {}: {err:#}", path.display());
+                self.show_notice(format!("Could not load {name}: {err:#}"));
+            }
+        }
+    }
+
+    /// Clear the board and stamp `pattern` in its center, taking over the pattern's rule.
+    fn place_pattern(&mut self, pattern: &Grid) -> anyhow::Result<()> {
+        let row = self.grid_height.saturating_sub(pattern.height()) / 2;
+        let col = self.grid_width.saturating_sub(pattern.width()) / 2;
+        self.place_pattern_at(pattern, row, col)
+    }
+
+    /// Clear the board and stamp `pattern` with its top-left corner at `row`, `col`, taking over the pattern's rule.
+    fn place_pattern_at(&mut self, pattern: &Grid, row: usize, col: usize) -> anyhow::Result<()> {
+        let (pattern_width, pattern_height) = (pattern.width(), pattern.height());
+        if row + pattern_height > self.grid_height || col + pattern_width > self.grid_width {
+            bail!(
+                "the {pattern_width}x{pattern_height} pattern does not fit on the {}x{} board",
+                self.grid_width,
+                self.grid_height
+            );
+        }
+        if self.gpu && !self.rule_locked && pattern.rule() != Rule::CONWAY {
+            bail!("the GPU simulation only supports B3/S23, not {}", pattern.rule());
+        }
+
+        self.grid.clear();
+        if !self.rule_locked {
+            self.grid.set_rule(pattern.rule());
+        }
+        self.grid.stamp(pattern, row, col);
+        self.restart();
+        Ok(())
+    }
+
+    /// One-key classroom demo: a Gosper glider gun in the top-left corner of a cleared board, running right away.
+    fn show_glider_gun(&mut self) {
+        let gun = patterns::GOSPER_GLIDER_GUN.grid();
+        match self.place_pattern_at(&gun, DEMO_GUN_MARGIN, DEMO_GUN_MARGIN) {
+            Ok(()) => self.paused = false,
+            Err(err) => {
+                log::error!("failed to place the glider gun: {err:#}");
+                self.show_notice(format!("Could not place the glider gun: {err:#}"));
+            }
+        }
+    }
+
+    /// Start counting generations afresh after the whole board was replaced.
+    fn restart(&mut self) {
+        self.gpu_generations = 0;
+        self.gpu_pending_steps = 0;
+        self.gpu_upload_pending = self.gpu;
+        self.tick_accumulator = Duration::ZERO;
+    }
+
+    fn show_notice(&mut self, text: String) {
+        self.notice = Some((text, Instant::now()));
+    }
+
+    /// Bring `grid` up to date with the GPU state before editing it on the CPU.
+    fn pull_from_gpu(&mut self, gpu_sim: &GpuSimulation, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if self.gpu_upload_pending {
+            return;
+        }
+        if let Err(err) = gpu_sim.read_back(device, queue, &mut self.grid) {
+            log::error!("failed to read grid back from the GPU: {err:#}");
+        }
+    }
+
+    /// Hand pending uploads, steps and the current layout to the GPU simulation.
+    fn prepare_gpu(&mut self, gpu_sim: &mut GpuSimulation, queue: &wgpu::Queue) {
+        if std::mem::take(&mut self.gpu_upload_pending) {
+            gpu_sim.upload(queue, &self.grid);
+        }
+        gpu_sim.queue_steps(std::mem::take(&mut self.gpu_pending_steps));
+
+        let width = self.window_size.width.max(1) as f32;
+        let height = self.window_size.height.max(1) as f32;
+        let layout = self.grid_layout();
+        gpu_sim.set_viewport(
+            queue,
+            [to_ndc(layout.offset[0], width), to_ndc_y(layout.offset[1], height)],
+            [layout.cell_size * 2.0 / width, layout.cell_size * 2.0 / height],
+            self.cell_inset(layout.cell_size) / layout.cell_size,
+        );
+    }
+
+    fn record_fps(&mut self, sample: f64) {
+        self.fps = Some(match self.fps {
+            Some(fps) => fps + (sample - fps) * FPS_SMOOTHING,
+            None => sample,
+        });
+    }
+
+    fn toggle_heatmap(&mut self) {
+        if self.gpu {
+            log::warn!("the age heatmap is only available with the CPU simulation");
+            return;
+        }
+        self.heatmap = !self.heatmap;
+        self.grid.set_age_tracking(self.heatmap);
+    }
+
+    fn toggle_grid_lines(&mut self) {
+        self.grid_lines = !self.grid_lines;
+    }
+
+    fn toggle_cell_gaps(&mut self) {
+        self.cell_gaps = !self.cell_gaps;
+    }
+
+    /// Widen (positive `steps`) or narrow the gap between cells, turning gaps on if they were off.
+    fn adjust_cell_gap(&mut self, steps: f32) {
+        self.cell_gaps = true;
+        self.cell_gap = (self.cell_gap + steps * CELL_GAP_STEP).clamp(CELL_GAP_STEP, MAX_CELL_GAP);
+    }
+
+    /// How far each cell is shrunk on every side, in pixels.
+    fn cell_inset(&self, cell_size: f32) -> f32 {
+        if !self.cell_gaps {
+            return 0.0;
+        }
+        // Keep at least a pixel of every cell, so tiny cells shrink to dots instead of vanishing or inverting.
+        (cell_size * self.cell_gap * 0.5).min((cell_size - 1.0) * 0.5).max(0.0)
+    }
+
+    /// Switch between wrapping and dead edges; the next generation already uses the new mode.
+    fn toggle_wrap(&mut self) {
+        if self.gpu {
+            log::warn!("dead edges are only available with the CPU simulation");
+            return;
+        }
+        let edge_mode = match self.grid.edge_modes() {
+            (EdgeMode::Toroidal, EdgeMode::Toroidal) => EdgeMode::Dead,
+            _ => EdgeMode::Toroidal,
+        };
+        self.grid.set_edge_mode(edge_mode);
+        log::info!("edge mode: {edge_mode:?}");
+    }
+
+    /// Switch to the next of the Moore, Von Neumann and hex neighborhoods.
+    fn cycle_neighborhood(&mut self) {
+        if self.gpu {
+            log::warn!("other neighborhoods are only available with the CPU simulation");
+            return;
+        }
+        let neighborhood = match self.grid.neighborhood() {
+            Neighborhood::Moore => Neighborhood::VonNeumann,
+            Neighborhood::VonNeumann => Neighborhood::Hex,
+            Neighborhood::Hex => Neighborhood::Moore,
+        };
+        self.grid.set_neighborhood(neighborhood);
+        self.show_notice(format!("Neighborhood: {neighborhood:?}"));
+    }
+
+    /// Move the keyboard cursor, showing it in the middle of the board first. It wraps around toroidal edges and
+    /// stops at any others.
+    fn move_key_cursor(&mut self, rows: isize, cols: isize) {
+        let Some((row, col)) = self.key_cursor else {
+            self.key_cursor = Some((self.grid_height / 2, self.grid_width / 2));
+            return;
+        };
+        let (horizontal, vertical) = self.grid.edge_modes();
+        let step = |position: usize, delta: isize, len: usize, edge_mode: EdgeMode| {
+            if edge_mode == EdgeMode::Toroidal {
+                (position as isize + delta).rem_euclid(len as isize) as usize
+            } else {
+                position.saturating_add_signed(delta).min(len - 1)
+            }
+        };
+        self.key_cursor = Some((step(row, rows, self.grid_height, vertical), step(col, cols, self.grid_width, horizontal)));
+    }
+
+    /// Toggle the cell under the keyboard cursor, or show the cursor if it is hidden.
+    fn toggle_at_key_cursor(&mut self) {
+        match self.key_cursor {
+            Some((row, col)) => {
+                self.grid.toggle(row, col);
+                self.gpu_upload_pending = self.gpu;
+            }
+            None => self.move_key_cursor(0, 0),
+        }
+    }
+
+    fn hide_key_cursor(&mut self) {
+        self.key_cursor = None;
+    }
+
+    fn toggle_high_contrast(&mut self) {
+        self.high_contrast = !self.high_contrast;
+    }
+
+    fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    fn toggle_minimap(&mut self) {
+        self.show_minimap = !self.show_minimap;
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.tick_accumulator = Duration::ZERO;
     }
 
-    fn handle_click(&mut self, position: [f32; 2]) {
+    /// Advance exactly one generation; only meaningful while paused.
+    fn step(&mut self) {
+        if self.paused {
+            self.advance();
+        }
+    }
+
+    /// Toggle the clicked cell. Returns `true` for a click on the Randomize button, which the caller handles so
+    /// that every board gets the same seed.
+    fn handle_click(&mut self, position: [f32; 2]) -> bool {
         if self.button_rect().contains(position) {
-            self.randomize();
+            return true;
+        }
+        if position[1] >= UI_HEIGHT {
+            if let Some((row, col)) = self.grid_layout().cell_at(position) {
+                self.grid.toggle(row, col);
+                self.gpu_upload_pending = self.gpu;
+            }
+        }
+        false
+    }
+
+    /// Fit the grid into the viewport below the UI bar with square cells of at least one pixel, then apply zoom and
+    /// pan.
+    fn grid_layout(&self) -> GridLayout {
+        let left = self.viewport.min[0];
+        let width = (self.viewport.max[0] - left).max(1.0);
+        let height = self.window_size.height.max(1) as f32;
+
+        let usable_height = (height - UI_HEIGHT).max(1.0);
+        // Sub-pixel cells would alias badly, so a board too large for the window overflows it instead.
+        let fit_cell_size = (width / self.grid_width as f32).min(usable_height / self.grid_height as f32);
+        let cell_size = fit_cell_size.max(1.0) * self.zoom;
+        let grid_pixel_width = cell_size * self.grid_width as f32;
+        let grid_pixel_height = cell_size * self.grid_height as f32;
+        GridLayout {
+            offset: [
+                left + (width - grid_pixel_width) * 0.5 + self.pan[0],
+                UI_HEIGHT + (usable_height - grid_pixel_height) * 0.5 + self.pan[1],
+            ],
+            cell_size,
+            columns: self.grid_width,
+            rows: self.grid_height,
+            overflows: fit_cell_size < 1.0,
+            hex: self.grid.neighborhood() == Neighborhood::Hex,
         }
     }
 
     fn button_rect(&self) -> Rect {
-        let width = self.window_size.width.max(1) as f32;
+        let right = self.viewport.max[0];
         Rect {
-            min: [width - BUTTON_PADDING - BUTTON_WIDTH, BUTTON_PADDING + BUTTON_VERTICAL_OFFSET],
-            max: [width - BUTTON_PADDING, BUTTON_PADDING + BUTTON_VERTICAL_OFFSET + BUTTON_HEIGHT],
+            min: [right - BUTTON_PADDING - BUTTON_WIDTH, BUTTON_PADDING + BUTTON_VERTICAL_OFFSET],
+            max: [right - BUTTON_PADDING, BUTTON_PADDING + BUTTON_VERTICAL_OFFSET + BUTTON_HEIGHT],
         }
     }
 
@@ -458,48 +1309,126 @@ impl GameOfLifeApp {
 
         let width = self.window_size.width.max(1) as f32;
         let height = self.window_size.height.max(1) as f32;
-
-        let usable_height = (height - UI_HEIGHT).max(1.0);
-        let cell_size = ((width / GRID_WIDTH as f32).min(usable_height / GRID_HEIGHT as f32)).max(1.0);
-        let grid_pixel_width = cell_size * GRID_WIDTH as f32;
-        let grid_pixel_height = cell_size * GRID_HEIGHT as f32;
-        let grid_offset_x = (width - grid_pixel_width) * 0.5;
-        let grid_offset_y = UI_HEIGHT + (usable_height - grid_pixel_height) * 0.5;
-
-        for (row_index, row) in self.grid.cells.iter().enumerate() {
-            for (col_index, cell) in row.iter().enumerate() {
-                let x = grid_offset_x + col_index as f32 * cell_size;
+        let [view_left, view_right] = [self.viewport.min[0], self.viewport.max[0]];
+
+        let layout = self.grid_layout();
+        let GridLayout {
+            offset: [grid_offset_x, grid_offset_y],
+            cell_size,
+            overflows,
+            hex,
+            ..
+        } = layout;
+
+        // Only emit instances for cells that intersect the grid area.
+        let visible = |offset: f32, start: f32, end: f32, count: usize| {
+            let first = ((start - offset) / cell_size).floor().clamp(0.0, count as f32) as usize;
+            let last = ((end - offset) / cell_size).ceil().clamp(0.0, count as f32) as usize;
+            first..last.max(first)
+        };
+        let rows = visible(grid_offset_y, UI_HEIGHT, height, self.grid_height);
+        let cols = visible(grid_offset_x, view_left, view_right, self.grid_width);
+        // Shifted rows can show part of the column left of the first one that is visible unshifted.
+        let cols = if hex { cols.start.saturating_sub(1)..cols.end } else { cols };
+
+        let heatmap = Heatmap {
+            base: self.scheme,
+            ..Heatmap::DEFAULT
+        };
+        let scheme: &dyn ColorScheme = if self.heatmap { &heatmap } else { &self.scheme };
+        let states = self.grid.rule().states();
+        let inset = self.cell_inset(cell_size);
+
+        // In GPU mode the grid is drawn straight from the simulation's storage buffer.
+        let cpu_rows = if self.gpu { &[][..] } else { &self.grid.cells[rows.clone()] };
+        for (row_index, row) in rows.clone().zip(cpu_rows) {
+            for (col_index, cell) in cols.clone().zip(&row[cols.clone()]) {
+                // Cells cut by the viewport edge are clipped so they don't spill into a neighboring board.
+                let x = grid_offset_x + col_index as f32 * cell_size + layout.row_shift(row_index);
                 let y = grid_offset_y + row_index as f32 * cell_size;
-                let min = [to_ndc(x, width), to_ndc_y(y, height)];
-                let max = [to_ndc(x + cell_size, width), to_ndc_y(y + cell_size, height)];
-                let color = match cell {
-                    CellState::Alive => [0.95, 0.95, 0.95],
-                    CellState::Dead => [0.18, 0.18, 0.22],
-                };
+                let left = (x + inset).max(view_left);
+                let right = (x + cell_size - inset).min(view_right).max(left);
+                let min = [to_ndc(left, width), to_ndc_y(y + inset, height)];
+                let max = [to_ndc(right, width), to_ndc_y(y + cell_size - inset, height)];
+                let color = scheme.color(*cell, self.grid.age(row_index, col_index), states);
                 self.instances.push(CellInstance { min, max, color, _pad: 0.0 });
             }
         }
 
+        // Lines sit centered on the cell edges, spanning only the visible part of the grid.
+        if self.grid_lines && cell_size >= GRID_LINE_MIN_CELL_SIZE && !rows.is_empty() && !cols.is_empty() {
+            let half = GRID_LINE_WIDTH * 0.5;
+            let left = (grid_offset_x + cols.start as f32 * cell_size).max(view_left);
+            let right = (grid_offset_x + cols.end as f32 * cell_size).min(view_right);
+            let top = grid_offset_y + rows.start as f32 * cell_size;
+            let bottom = grid_offset_y + rows.end as f32 * cell_size;
+            for row in rows.start..=rows.end {
+                let y = grid_offset_y + row as f32 * cell_size;
+                let line = Rect {
+                    min: [left, y - half],
+                    max: [right, y + half],
+                };
+                push_rect(&mut self.ui_vertices, line, GRID_LINE_COLOR, [width, height]);
+            }
+            // Column edges zigzag on hex boards, so only the rows are lined there.
+            for col in (cols.start..=cols.end).filter(|_| !hex) {
+                let x = grid_offset_x + col as f32 * cell_size;
+                if x < view_left || x > view_right {
+                    continue;
+                }
+                let line = Rect {
+                    min: [x - half, top],
+                    max: [x + half, bottom],
+                };
+                push_rect(&mut self.ui_vertices, line, GRID_LINE_COLOR, [width, height]);
+            }
+        }
+
+        if let Some((row, col)) = self.key_cursor {
+            let x = grid_offset_x + col as f32 * cell_size + layout.row_shift(row);
+            let cell = Rect {
+                min: [x, grid_offset_y + row as f32 * cell_size],
+                max: [x + cell_size, grid_offset_y + (row + 1) as f32 * cell_size],
+            };
+            if cell.min[0] >= view_left && cell.max[0] <= view_right {
+                let thickness = KEY_CURSOR_WIDTH.min(cell_size * 0.25).max(1.0);
+                push_outline(&mut self.ui_vertices, cell, thickness, KEY_CURSOR_COLOR, [width, height]);
+            }
+        }
+
+        let palette = if self.high_contrast {
+            UiPalette::HIGH_CONTRAST
+        } else {
+            UiPalette::DEFAULT
+        };
+
+        // The grid can be panned under the UI bar, so paint its background over it.
+        let ui_bar = Rect {
+            min: [view_left, 0.0],
+            max: [view_right, UI_HEIGHT],
+        };
+        push_rect(&mut self.ui_vertices, ui_bar, palette.bar, [width, height]);
+
         let header_line = Rect {
-            min: [0.0, UI_HEIGHT - 4.0],
-            max: [width, UI_HEIGHT],
+            min: [view_left, UI_HEIGHT - 4.0],
+            max: [view_right, UI_HEIGHT],
         };
-        push_rect(&mut self.ui_vertices, header_line, [0.15, 0.15, 0.2], [width, height]);
+        push_rect(&mut self.ui_vertices, header_line, palette.header_line, [width, height]);
+
+        // Boards further right are separated from their left neighbor by a line in the header color.
+        if view_left > 0.0 {
+            let divider = Rect {
+                min: [view_left - 1.0, 0.0],
+                max: [view_left + 1.0, height],
+            };
+            push_rect(&mut self.ui_vertices, divider, palette.header_line, [width, height]);
+        }
 
         let button_rect = self.button_rect();
         let hovered = self.cursor_position.map(|pos| button_rect.contains(pos)).unwrap_or(false);
-        let button_color = if hovered { [0.35, 0.45, 0.75] } else { [0.25, 0.33, 0.55] };
+        let button_color = if hovered { palette.button_hovered } else { palette.button };
         push_rect(&mut self.ui_vertices, button_rect, button_color, [width, height]);
 
-        draw_text(
-            &mut self.ui_vertices,
-            "Game of Life",
-            [BUTTON_PADDING, BUTTON_PADDING],
-            TEXT_SCALE_HEADING,
-            [0.9, 0.9, 0.95],
-            [width, height],
-        );
-
         let button_text = "Randomize";
         let text_width = text_pixel_width(button_text) * TEXT_SCALE_BUTTON;
         let text_height = FONT_HEIGHT as f32 * TEXT_SCALE_BUTTON;
@@ -510,50 +1439,179 @@ impl GameOfLifeApp {
             button_text,
             [origin_x, origin_y],
             TEXT_SCALE_BUTTON,
-            [0.95, 0.95, 0.98],
+            palette.button_text,
+            palette.glyph_bleed,
+            [width, height],
+        );
+
+        let fps_text = match self.fps {
+            Some(fps) => format!("FPS {fps:.0}"),
+            None => "FPS --".to_string(),
+        };
+        let stats_lines = [format!("Gen {}  {}/s", self.generation(), self.tick_rate), fps_text];
+        let stats_right = button_rect.min[0] - BUTTON_PADDING;
+        let line_height = FONT_HEIGHT as f32 * TEXT_SCALE_STATS;
+        for (index, line) in stats_lines.iter().enumerate() {
+            let origin_x = stats_right - text_pixel_width(line) * TEXT_SCALE_STATS;
+            let origin_y = if index == 0 {
+                button_rect.min[1]
+            } else {
+                button_rect.max[1] - line_height
+            };
+            draw_text(
+                &mut self.ui_vertices,
+                line,
+                [origin_x, origin_y],
+                TEXT_SCALE_STATS,
+                palette.stats,
+                palette.glyph_bleed,
+                [width, height],
+            );
+        }
+
+        // The heading shrinks when it would run into the stats, e.g. on half a window.
+        let stats_left = stats_lines
+            .iter()
+            .map(|line| stats_right - text_pixel_width(line) * TEXT_SCALE_STATS)
+            .fold(stats_right, f32::min);
+        let heading_room = stats_left - BUTTON_PADDING - (view_left + BUTTON_PADDING);
+        let heading_scale = (heading_room / text_pixel_width(&self.title)).clamp(1.0, TEXT_SCALE_HEADING);
+        draw_text(
+            &mut self.ui_vertices,
+            &self.title,
+            [view_left + BUTTON_PADDING, BUTTON_PADDING],
+            heading_scale,
+            palette.heading,
+            palette.glyph_bleed,
             [width, height],
         );
 
+        if self.notice.as_ref().is_some_and(|(_, posted)| posted.elapsed() >= NOTICE_DURATION) {
+            self.notice = None;
+        }
+        // Fully zoomed out, a board that still overflows would otherwise just look cut off.
+        let overflow_warning = (overflows && self.zoom <= MIN_ZOOM).then_some(OVERFLOW_WARNING);
+        if let Some(text) = self.notice.as_ref().map(|(text, _)| text.as_str()).or(overflow_warning) {
+            let text_height = FONT_HEIGHT as f32 * TEXT_SCALE_STATS;
+            let backdrop = Rect {
+                min: [view_left, UI_HEIGHT],
+                max: [view_right, UI_HEIGHT + text_height + BUTTON_PADDING],
+            };
+            push_rect(&mut self.ui_vertices, backdrop, palette.bar, [width, height]);
+            let text_room = view_right - view_left - BUTTON_PADDING * 2.0;
+            let text_scale = (text_room / text_pixel_width(text)).clamp(1.0, TEXT_SCALE_STATS);
+            draw_text(
+                &mut self.ui_vertices,
+                text,
+                [view_left + BUTTON_PADDING, UI_HEIGHT + BUTTON_PADDING * 0.5],
+                text_scale,
+                palette.notice,
+                palette.glyph_bleed,
+                [width, height],
+            );
+        }
+
+        if self.show_minimap {
+            let area = Rect {
+                min: [view_left, UI_HEIGHT],
+                max: [view_right, height],
+            };
+            // The CPU copy of a GPU simulation is stale, so only the viewport is shown there.
+            let grid = (!self.gpu).then_some(&self.grid);
+            let colors = [self.scheme.dead, self.scheme.alive];
+            draw_minimap(
+                &mut self.ui_vertices,
+                grid,
+                [self.grid_width, self.grid_height],
+                [rows, cols],
+                area,
+                colors,
+                [width, height],
+            );
+        }
+
+        // Last, so it covers everything else; the simulation keeps running underneath.
+        if self.show_help {
+            let area = Rect {
+                min: [view_left, UI_HEIGHT],
+                max: [view_right, height],
+            };
+            draw_help(&mut self.ui_vertices, &palette, area, [width, height]);
+        }
+
+        if let Some(hook) = &mut self.on_build_frame {
+            hook(&self.grid, &mut self.ui_vertices, [width, height]);
+        }
+
         (&self.instances, &self.ui_vertices)
     }
 }
 
+/// Append two triangles covering `rect`, given in pixels from the top-left corner of the window.
 fn push_rect(vertices: &mut Vec<Vertex>, rect: Rect, color: [f32; 3], window_size: [f32; 2]) {
+    push_translucent_rect(vertices, rect, color, 1.0, window_size);
+}
+
+/// Like [`push_rect`], blending `color` over what is behind it with the given opacity.
+fn push_translucent_rect(vertices: &mut Vec<Vertex>, rect: Rect, color: [f32; 3], alpha: f32, window_size: [f32; 2]) {
     let [width, height] = window_size;
     let x0 = to_ndc(rect.min[0], width);
     let y0 = to_ndc_y(rect.min[1], height);
     let x1 = to_ndc(rect.max[0], width);
     let y1 = to_ndc_y(rect.max[1], height);
 
-    vertices.push(Vertex { position: [x0, y1], color });
-    vertices.push(Vertex { position: [x1, y1], color });
-    vertices.push(Vertex { position: [x0, y0], color });
-    vertices.push(Vertex { position: [x0, y0], color });
-    vertices.push(Vertex { position: [x1, y1], color });
-    vertices.push(Vertex { position: [x1, y0], color });
+    for position in [[x0, y1], [x1, y1], [x0, y0], [x0, y0], [x1, y1], [x1, y0]] {
+        vertices.push(Vertex { position, color, alpha });
+    }
 }
 
+/// Draw the inside border of `rect`, `thickness` pixels wide.
+fn push_outline(vertices: &mut Vec<Vertex>, rect: Rect, thickness: f32, color: [f32; 3], window_size: [f32; 2]) {
+    let Rect { min, max } = rect;
+    let edges = [
+        Rect {
+            min,
+            max: [max[0], min[1] + thickness],
+        },
+        Rect {
+            min: [min[0], max[1] - thickness],
+            max,
+        },
+        Rect {
+            min,
+            max: [min[0] + thickness, max[1]],
+        },
+        Rect {
+            min: [max[0] - thickness, min[1]],
+            max,
+        },
+    ];
+    for edge in edges {
+        push_rect(vertices, edge, color, window_size);
+    }
+}
+
+/// Pixel column `x` of a window `width` pixels wide in normalized device coordinates, from -1 (left) to 1 (right).
 fn to_ndc(x: f32, width: f32) -> f32 {
     (x / width) * 2.0 - 1.0
 }
 
+/// Like [`to_ndc`] for pixel row `y`, flipped since device coordinates point up: 1 is the top edge.
 fn to_ndc_y(y: f32, height: f32) -> f32 {
     1.0 - (y / height) * 2.0
 }
 
 fn text_pixel_width(text: &str) -> f32 {
-    let mut units = 0.0;
-    for ch in text.chars() {
-        if ch == ' ' || glyph_bits(ch).is_some() {
-            units += (FONT_WIDTH as f32) + 1.0;
-        }
-    }
+    let units = text.chars().count() as f32 * (FONT_WIDTH as f32 + 1.0);
     (units - 1.0).max(0.0)
 }
 
-fn draw_text(vertices: &mut Vec<Vertex>, text: &str, origin: [f32; 2], scale: f32, color: [f32; 3], window_size: [f32; 2]) {
+/// Draw `text` with its top-left corner at `origin`. Each glyph pixel is `scale` pixels wide, plus `bleed * scale` on
+/// every side to thicken the strokes.
+fn draw_text(vertices: &mut Vec<Vertex>, text: &str, origin: [f32; 2], scale: f32, color: [f32; 3], bleed: f32, window_size: [f32; 2]) {
+    let bleed = bleed * scale;
     let mut cursor_x = origin[0];
-    for ch in text.to_uppercase().chars() {
+    for ch in text.chars() {
         if ch == ' ' {
             cursor_x += (FONT_WIDTH as f32 + 1.0) * scale;
             continue;
@@ -563,8 +1621,11 @@ fn draw_text(vertices: &mut Vec<Vertex>, text: &str, origin: [f32; 2], scale: f3
                 for col in 0..FONT_WIDTH {
                     if (bits >> (FONT_WIDTH - 1 - col)) & 1 == 1 {
                         let rect = Rect {
-                            min: [cursor_x + col as f32 * scale, origin[1] + row as f32 * scale],
-                            max: [cursor_x + (col as f32 + 1.0) * scale, origin[1] + (row as f32 + 1.0) * scale],
+                            min: [cursor_x + col as f32 * scale - bleed, origin[1] + row as f32 * scale - bleed],
+                            max: [
+                                cursor_x + (col as f32 + 1.0) * scale + bleed,
+                                origin[1] + (row as f32 + 1.0) * scale + bleed,
+                            ],
                         };
                         push_rect(vertices, rect, color, window_size);
                     }
@@ -575,27 +1636,108 @@ fn draw_text(vertices: &mut Vec<Vertex>, text: &str, origin: [f32; 2], scale: f3
     }
 }
 
-fn glyph_bits(ch: char) -> Option<[u8; FONT_HEIGHT]> {
-    match ch {
-        'A' => Some([0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
-        'D' => Some([0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110]),
-        'E' => Some([0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
-        'F' => Some([0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
-        'G' => Some([0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
-        'I' => Some([0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b11111]),
-        'L' => Some([0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
-        'M' => Some([0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
-        'N' => Some([0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001, 0b10001]),
-        'O' => Some([0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
-        'R' => Some([0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
-        'Z' => Some([0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
-        _ => None,
+/// Panel listing [`SHORTCUTS`] centered in `area`, with the text scaled down when the panel would not fit.
+fn draw_help(vertices: &mut Vec<Vertex>, palette: &UiPalette, area: Rect, window_size: [f32; 2]) {
+    // Keys are padded to one width so the descriptions line up; every glyph is equally wide.
+    let key_chars = SHORTCUTS.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let lines: Vec<String> = SHORTCUTS.iter().map(|(key, action)| format!("{key:<key_chars$}  {action}")).collect();
+    let title = "Controls";
+
+    // The title is followed by an empty line; lines are spaced by two glyph pixels.
+    let line_pitch = FONT_HEIGHT as f32 + 2.0;
+    let text_width = lines.iter().map(|line| text_pixel_width(line)).fold(text_pixel_width(title), f32::max);
+    let text_height = (lines.len() + 2) as f32 * line_pitch - 2.0;
+    let room = [
+        area.max[0] - area.min[0] - 4.0 * BUTTON_PADDING,
+        area.max[1] - area.min[1] - 4.0 * BUTTON_PADDING,
+    ];
+    let scale = (room[0] / text_width).min(room[1] / text_height).clamp(1.0, HELP_TEXT_SCALE);
+
+    let panel_size = [text_width * scale + 2.0 * BUTTON_PADDING, text_height * scale + 2.0 * BUTTON_PADDING];
+    let center = [(area.min[0] + area.max[0]) * 0.5, (area.min[1] + area.max[1]) * 0.5];
+    let panel = Rect {
+        min: [center[0] - panel_size[0] * 0.5, center[1] - panel_size[1] * 0.5],
+        max: [center[0] + panel_size[0] * 0.5, center[1] + panel_size[1] * 0.5],
+    };
+    push_translucent_rect(vertices, panel, palette.bar, HELP_BACKDROP_ALPHA, window_size);
+    push_outline(vertices, panel, 2.0, palette.header_line, window_size);
+
+    let left = panel.min[0] + BUTTON_PADDING;
+    let top = panel.min[1] + BUTTON_PADDING;
+    draw_text(vertices, title, [left, top], scale, palette.heading, palette.glyph_bleed, window_size);
+    for (index, line) in lines.iter().enumerate() {
+        let origin = [left, top + (index + 2) as f32 * line_pitch * scale];
+        draw_text(vertices, line, origin, scale, palette.stats, palette.glyph_bleed, window_size);
     }
 }
 
+/// Overview of a `size` (columns, rows) grid in the bottom-right corner of `area`, outlining the `visible` rows
+/// and columns. Cells are aggregated into at most [`MINIMAP_MAX_BLOCKS`] blocks per side, each brighter the more of
+/// its cells are alive; without a `grid` only the outlines are drawn. `colors` are the dead and alive colors.
+fn draw_minimap(
+    vertices: &mut Vec<Vertex>,
+    grid: Option<&Grid>,
+    size: [usize; 2],
+    visible: [Range<usize>; 2],
+    area: Rect,
+    colors: [[f32; 3]; 2],
+    window_size: [f32; 2],
+) {
+    let [columns, rows] = size;
+    let scale = MINIMAP_SIZE / columns.max(rows).max(1) as f32;
+    let map_size = [columns as f32 * scale, rows as f32 * scale];
+    let map = Rect {
+        min: [area.max[0] - MINIMAP_MARGIN - map_size[0], area.max[1] - MINIMAP_MARGIN - map_size[1]],
+        max: [area.max[0] - MINIMAP_MARGIN, area.max[1] - MINIMAP_MARGIN],
+    };
+    // Leave boards too small to hold the minimap alone.
+    if map.min[0] < area.min[0] + MINIMAP_MARGIN || map.min[1] < area.min[1] + MINIMAP_MARGIN {
+        return;
+    }
+    let [dead, alive] = colors;
+    push_translucent_rect(vertices, map, dead, MINIMAP_BACKDROP_ALPHA, window_size);
+
+    // Cells along each side of a block, so no more than `MINIMAP_MAX_BLOCKS` are drawn along either side.
+    let block = columns.max(rows).div_ceil(MINIMAP_MAX_BLOCKS).max(1);
+    let to_map = |row: usize, col: usize| [map.min[0] + col as f32 * scale, map.min[1] + row as f32 * scale];
+    for row in (0..rows).step_by(block) {
+        for col in (0..columns).step_by(block) {
+            let Some(alive_cells) = grid
+                .map(|grid| grid.count_alive_in_region(row, col, block, block))
+                .filter(|count| *count > 0)
+            else {
+                continue;
+            };
+            let (bottom, right) = ((row + block).min(rows), (col + block).min(columns));
+            let density = alive_cells as f32 / ((bottom - row) * (right - col)) as f32;
+            // Even a single alive cell stays visible against the dead background.
+            let color = mix(dead, alive, 0.35 + 0.65 * density);
+            let rect = Rect {
+                min: to_map(row, col),
+                max: to_map(bottom, right),
+            };
+            push_rect(vertices, rect, color, window_size);
+        }
+    }
+
+    let [visible_rows, visible_cols] = visible;
+    let viewport = Rect {
+        min: to_map(visible_rows.start, visible_cols.start),
+        max: to_map(visible_rows.end, visible_cols.end),
+    };
+    push_outline(vertices, map, 1.0, alive, window_size);
+    push_outline(vertices, viewport, 2.0, MINIMAP_VIEWPORT_COLOR, window_size);
+}
+
 fn key_matches(event: &KeyEvent, target: &str) -> bool {
     match &event.logical_key {
         Key::Named(NamedKey::Space) => target.eq_ignore_ascii_case("SPACE"),
+        Key::Named(NamedKey::Enter) => target.eq_ignore_ascii_case("ENTER"),
+        Key::Named(NamedKey::Escape) => target.eq_ignore_ascii_case("ESCAPE"),
+        Key::Named(NamedKey::ArrowUp) => target.eq_ignore_ascii_case("UP"),
+        Key::Named(NamedKey::ArrowDown) => target.eq_ignore_ascii_case("DOWN"),
+        Key::Named(NamedKey::ArrowLeft) => target.eq_ignore_ascii_case("LEFT"),
+        Key::Named(NamedKey::ArrowRight) => target.eq_ignore_ascii_case("RIGHT"),
         Key::Character(text) => text.eq_ignore_ascii_case(target),
         _ => false,
     }
@@ -606,30 +1748,103 @@ struct VulkanApp {
     window: Option<Arc<Window>>,
     window_id: Option<WindowId>,
     state: Option<State>,
-    app: Option<GameOfLifeApp>,
+    /// One board, or one per rule side by side when comparing rules.
+    apps: Vec<GameOfLifeApp>,
+    /// Every board's instances and UI vertices, concatenated when there is more than one board.
+    frame_instances: Vec<CellInstance>,
+    frame_vertices: Vec<Vertex>,
     last_cursor: [f32; 2],
     frame_count: u32,
     last_fps_log: Instant,
+    /// Shortest time between two frames when the frame rate is capped.
+    frame_interval: Option<Duration>,
+    /// Position in the configured background followed by [`BACKGROUND_PRESETS`].
+    background: usize,
+    last_frame: Instant,
+    args: Args,
+    config: Config,
 }
 
 impl VulkanApp {
-    fn new() -> Self {
+    fn new(args: Args, config: Config) -> Self {
         let attrs = Window::default_attributes()
-            .with_title("Game of Life - Vulkan")
-            .with_inner_size(PhysicalSize::new(1280, 720));
+            .with_title(window_title("wgpu"))
+            .with_inner_size(config.window_size(args.rules().len()));
         Self {
             window_attrs: attrs,
             window: None,
             window_id: None,
             state: None,
-            app: None,
+            apps: Vec::new(),
+            frame_instances: Vec::new(),
+            frame_vertices: Vec::new(),
             last_cursor: [0.0, 0.0],
             frame_count: 0,
             last_fps_log: Instant::now(),
+            frame_interval: config.max_fps.map(|fps| Duration::from_secs_f64(1.0 / f64::from(fps))),
+            last_frame: Instant::now(),
+            background: 0,
+            args,
+            config,
+        }
+    }
+
+    /// Switch the window background to the next of the configured color and the presets.
+    fn cycle_background(&mut self) {
+        let backgrounds: Vec<[f32; 3]> = std::iter::once(self.config.background_color).chain(BACKGROUND_PRESETS).collect();
+        self.background = (self.background + 1) % backgrounds.len();
+        if let Some(state) = self.state.as_mut() {
+            state.clear_color = backgrounds[self.background];
+            log::info!("background color: {:?}", state.clear_color);
+        }
+    }
+
+    /// Split the window into equal columns, one per board.
+    fn layout_apps(&mut self, size: PhysicalSize<u32>) {
+        let column_width = size.width as f32 / self.apps.len().max(1) as f32;
+        for (index, app) in self.apps.iter_mut().enumerate() {
+            let viewport = Rect {
+                min: [index as f32 * column_width, 0.0],
+                max: [(index + 1) as f32 * column_width, size.height as f32],
+            };
+            app.resize(size, viewport);
+        }
+    }
+
+    /// The board whose part of the window contains `position`.
+    fn app_at(&mut self, position: [f32; 2]) -> Option<&mut GameOfLifeApp> {
+        self.apps.iter_mut().find(|app| app.viewport.contains(position))
+    }
+
+    /// Give every board the same fresh random cells.
+    fn randomize(&mut self) {
+        let seed = RandomState::new().build_hasher().finish();
+        for app in &mut self.apps {
+            app.randomize_seeded(seed);
         }
     }
 }
 
+/// Build every board's frame. A single board's buffers are used as they are; several are concatenated into
+/// `instances` and `vertices`, which works because each board only draws inside its own viewport.
+fn build_frame<'a>(
+    apps: &'a mut [GameOfLifeApp],
+    instances: &'a mut Vec<CellInstance>,
+    vertices: &'a mut Vec<Vertex>,
+) -> (&'a [CellInstance], &'a [Vertex]) {
+    if let [app] = apps {
+        return app.build_frame();
+    }
+    instances.clear();
+    vertices.clear();
+    for app in apps {
+        let (app_instances, app_vertices) = app.build_frame();
+        instances.extend_from_slice(app_instances);
+        vertices.extend_from_slice(app_vertices);
+    }
+    (instances, vertices)
+}
+
 impl ApplicationHandler<()> for VulkanApp {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if self.window.is_some() {
@@ -639,32 +1854,49 @@ impl ApplicationHandler<()> for VulkanApp {
         let window = Arc::new(window);
         let window_id = window.id();
 
-        let state = pollster::block_on(State::new(window.clone())).expect("failed to create GPU state");
-        let app = GameOfLifeApp::new(state.size);
+        let state = pollster::block_on(State::new(window.clone(), &self.args, &self.config)).expect("failed to create GPU state");
+        window.set_title(&window_title(&format!("{:?}", state.backend)));
+        let rules = self.args.rules();
+        let comparing = rules.len() > 1;
+        self.apps = rules
+            .into_iter()
+            .map(|rule| {
+                let app = GameOfLifeApp::new(state.size, &self.config, rule, self.args.gpu);
+                if comparing {
+                    app.compared()
+                } else {
+                    app
+                }
+            })
+            .collect();
+        self.layout_apps(state.size);
+        if comparing {
+            self.randomize();
+        }
         window.request_redraw();
 
         self.window = Some(window);
         self.window_id = Some(window_id);
         self.state = Some(state);
-        self.app = Some(app);
     }
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
         if Some(window_id) != self.window_id {
             return;
         }
+        // Frames are drawn on demand, so anything other than a redraw may change what is on screen.
+        if !matches!(event, WindowEvent::RedrawRequested) {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
         match event {
             WindowEvent::CloseRequested => event_loop.exit(),
             WindowEvent::Resized(size) => {
                 if let Some(state) = self.state.as_mut() {
                     state.resize(size);
                 }
-                if let Some(app) = self.app.as_mut() {
-                    app.resize(size);
-                }
-                if let Some(window) = &self.window {
-                    window.request_redraw();
-                }
+                self.layout_apps(size);
             }
             WindowEvent::ScaleFactorChanged { mut inner_size_writer, .. } => {
                 if let Some(state) = self.state.as_ref() {
@@ -674,35 +1906,147 @@ impl ApplicationHandler<()> for VulkanApp {
             }
             WindowEvent::CursorMoved { position, .. } => {
                 self.last_cursor = [position.x as f32, position.y as f32];
-                if let Some(app) = self.app.as_mut() {
+                for app in &mut self.apps {
                     app.cursor_position = Some(self.last_cursor);
+                    app.drag_to(self.last_cursor);
                 }
             }
-            WindowEvent::MouseInput { state, button, .. } => {
-                if button == MouseButton::Left && state == ElementState::Released {
-                    if let Some(app) = self.app.as_mut() {
-                        app.handle_click(self.last_cursor);
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32 / PIXELS_PER_SCROLL_LINE,
+                };
+                let cursor = self.last_cursor;
+                if let Some(app) = self.app_at(cursor) {
+                    app.zoom_at(cursor, lines);
+                }
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Middle,
+                ..
+            } => {
+                let cursor = self.last_cursor;
+                match state {
+                    ElementState::Pressed => {
+                        if let Some(app) = self.app_at(cursor) {
+                            app.begin_pan(cursor);
+                        }
+                    }
+                    ElementState::Released => self.apps.iter_mut().for_each(GameOfLifeApp::end_pan),
+                }
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } => {
+                let cursor = self.last_cursor;
+                let clicked = self.apps.iter_mut().find(|app| app.viewport.contains(cursor));
+                let mut randomize = false;
+                if let (Some(gpu_state), Some(app)) = (self.state.as_ref(), clicked) {
+                    if let Some(gpu_sim) = &gpu_state.gpu_sim {
+                        app.pull_from_gpu(gpu_sim, &gpu_state.device, &gpu_state.queue);
                     }
+                    randomize = app.handle_click(cursor);
+                }
+                if randomize {
+                    self.randomize();
+                }
+            }
+            WindowEvent::DroppedFile(path) => {
+                for app in &mut self.apps {
+                    app.load_pattern(&path);
                 }
             }
-            WindowEvent::KeyboardInput { event, .. } => {
-                if event.state == ElementState::Pressed {
-                    if let Some(app) = self.app.as_mut() {
-                        if key_matches(&event, "R") || key_matches(&event, "SPACE") {
-                            app.randomize();
+            WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                if key_matches(&event, "P") && !event.repeat {
+                    if let Some(state) = self.state.as_mut() {
+                        state.request_screenshot();
+                    }
+                } else if key_matches(&event, "F") && !event.repeat {
+                    if let Some(state) = self.state.as_mut() {
+                        state.cycle_present_mode();
+                    }
+                } else if key_matches(&event, "K") && !event.repeat {
+                    self.cycle_background();
+                } else if key_matches(&event, "R") {
+                    self.randomize();
+                } else {
+                    // Every board follows the same controls, so compared rules stay in step.
+                    for app in &mut self.apps {
+                        // Space edits instead of pausing while the keyboard cursor is shown.
+                        if key_matches(&event, "ENTER") || (key_matches(&event, "SPACE") && app.key_cursor.is_some()) {
+                            if !event.repeat {
+                                if let Some(state) = &self.state {
+                                    if let Some(gpu_sim) = &state.gpu_sim {
+                                        app.pull_from_gpu(gpu_sim, &state.device, &state.queue);
+                                    }
+                                }
+                                app.toggle_at_key_cursor();
+                            }
+                        } else if key_matches(&event, "UP") {
+                            app.move_key_cursor(-1, 0);
+                        } else if key_matches(&event, "DOWN") {
+                            app.move_key_cursor(1, 0);
+                        } else if key_matches(&event, "LEFT") {
+                            app.move_key_cursor(0, -1);
+                        } else if key_matches(&event, "RIGHT") {
+                            app.move_key_cursor(0, 1);
+                        } else if key_matches(&event, "ESCAPE") {
+                            app.hide_key_cursor();
+                        } else if key_matches(&event, "SPACE") && !event.repeat {
+                            app.toggle_pause();
+                        } else if key_matches(&event, "S") {
+                            app.step();
+                        } else if key_matches(&event, "C") && !event.repeat {
+                            app.toggle_heatmap();
+                        } else if key_matches(&event, "G") && !event.repeat {
+                            app.toggle_grid_lines();
+                        } else if key_matches(&event, "B") && !event.repeat {
+                            app.toggle_cell_gaps();
+                        } else if key_matches(&event, ".") {
+                            app.adjust_cell_gap(1.0);
+                        } else if key_matches(&event, ",") {
+                            app.adjust_cell_gap(-1.0);
+                        } else if key_matches(&event, "W") && !event.repeat {
+                            app.toggle_wrap();
+                        } else if key_matches(&event, "N") && !event.repeat {
+                            app.cycle_neighborhood();
+                        } else if key_matches(&event, "1") && !event.repeat {
+                            app.show_glider_gun();
+                        } else if key_matches(&event, "V") && !event.repeat {
+                            app.toggle_high_contrast();
+                        } else if key_matches(&event, "M") && !event.repeat {
+                            app.toggle_minimap();
+                        } else if (key_matches(&event, "H") || key_matches(&event, "?")) && !event.repeat {
+                            app.toggle_help();
+                        } else if key_matches(&event, "]") {
+                            app.faster();
+                        } else if key_matches(&event, "[") {
+                            app.slower();
+                        } else if key_matches(&event, "+") || key_matches(&event, "=") {
+                            // `=` is `+` without Shift on most layouts.
+                            app.step_speed_preset(true);
+                        } else if key_matches(&event, "-") {
+                            app.step_speed_preset(false);
                         }
                     }
                 }
             }
             WindowEvent::RedrawRequested => {
-                if let (Some(state), Some(app)) = (self.state.as_mut(), self.app.as_mut()) {
-                    app.update();
-                    let (instances, ui_vertices) = app.build_frame();
+                if let Some(state) = self.state.as_mut() {
+                    for app in &mut self.apps {
+                        app.update();
+                        if let Some(gpu_sim) = state.gpu_sim.as_mut() {
+                            app.prepare_gpu(gpu_sim, &state.queue);
+                        }
+                    }
+                    let (instances, ui_vertices) = build_frame(&mut self.apps, &mut self.frame_instances, &mut self.frame_vertices);
+                    self.last_frame = Instant::now();
                     if let Err(err) = state.render(instances, ui_vertices) {
-                        match err {
-                            wgpu::SurfaceError::Lost => state.resize(state.size),
-                            wgpu::SurfaceError::OutOfMemory => event_loop.exit(),
-                            _ => {}
+                        if !state.recover_from(err) {
+                            event_loop.exit();
                         }
                     } else {
                         self.frame_count += 1;
@@ -710,6 +2054,9 @@ impl ApplicationHandler<()> for VulkanApp {
                         if elapsed >= Duration::from_secs(1) {
                             let fps = self.frame_count as f64 / elapsed.as_secs_f64();
                             log::info!("fps: {:.1}", fps);
+                            for app in &mut self.apps {
+                                app.record_fps(fps);
+                            }
                             self.frame_count = 0;
                             self.last_fps_log = Instant::now();
                         }
@@ -720,18 +2067,83 @@ impl ApplicationHandler<()> for VulkanApp {
         }
     }
 
+    /// Keep drawing while a board is animating, no faster than the frame rate cap; otherwise sleep until input
+    /// arrives or a notice has to be cleared.
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        if let Some(window) = &self.window {
-            window.request_redraw();
+        let now = Instant::now();
+        let animating = self.apps.iter().any(GameOfLifeApp::is_animating);
+        let notice_expiry = self.apps.iter().filter_map(GameOfLifeApp::notice_expiry).min();
+        // Under a cap, sleep in the event loop until the next frame is due instead of rendering right away.
+        let next_frame = self.frame_interval.map(|interval| self.last_frame + interval).filter(|next| *next > now);
+        if (animating && next_frame.is_none()) || notice_expiry.is_some_and(|expiry| expiry <= now) {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
         }
-        event_loop.set_control_flow(ControlFlow::Poll);
+        let wake_at = match next_frame.filter(|_| animating) {
+            Some(next) => Some(notice_expiry.map_or(next, |expiry| expiry.min(next))),
+            None if animating => None,
+            None => notice_expiry,
+        };
+        event_loop.set_control_flow(wake_at.map_or(ControlFlow::Wait, ControlFlow::WaitUntil));
+    }
+}
+
+/// Read a pattern file, choosing the parser by extension: `.cells` is plaintext, anything else RLE.
+fn read_pattern(path: &Path) -> anyhow::Result<Grid> {
+    let text = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let is_plaintext = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("cells"));
+    let pattern = if is_plaintext {
+        Grid::from_plaintext(&text)?
+    } else {
+        Grid::from_rle(&text)?
+    };
+    Ok(pattern)
+}
+
+fn window_title(backend: &str) -> String {
+    format!("Game of Life - {backend} (R: randomize, Space: pause/resume, S: step)")
+}
+
+/// Parse a board edge length for `--width`/`--height`.
+fn parse_dimension(value: &str) -> Result<usize, String> {
+    let dimension: usize = value.parse().map_err(|err| format!("`{value}` is not a whole number: {err}"))?;
+    if dimension < MIN_GRID_DIMENSION {
+        return Err(format!("must be at least {MIN_GRID_DIMENSION}, got {dimension}"));
+    }
+    Ok(dimension)
+}
+
+/// Parse `--cell-size`, which must be a positive number of pixels.
+fn parse_cell_size(value: &str) -> Result<f32, String> {
+    let size: f32 = value.parse().map_err(|err| format!("`{value}` is not a number: {err}"))?;
+    if !size.is_finite() || size <= 0.0 {
+        return Err(format!("must be a positive number, got {value}"));
     }
+    Ok(size)
+}
+
+/// Parse `--max-fps`, which must be at least one frame per second.
+fn parse_max_fps(value: &str) -> Result<u32, String> {
+    let fps: u32 = value.parse().map_err(|err| format!("`{value}` is not a whole number: {err}"))?;
+    if fps == 0 {
+        return Err("must be at least 1".to_string());
+    }
+    Ok(fps)
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if args.gpu && args.rule != Rule::CONWAY {
+        bail!("--gpu only supports the B3/S23 rule, not {}", args.rule);
+    }
+    if args.gpu && args.compare.is_some() {
+        bail!("--compare is only available with the CPU simulation");
+    }
+    let config = args.config()?;
     env_logger::init();
     let event_loop = EventLoop::new()?;
-    let mut app = VulkanApp::new();
+    let mut app = VulkanApp::new(args, config);
     event_loop.run_app(&mut app)?;
     Ok(())
 }
@@ -0,0 +1,107 @@
+//! Reading a rendered frame back from the GPU and saving it as a PNG.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context};
+
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// A frame copied into a mappable buffer, waiting for the GPU to finish before it can be saved.
+pub struct PendingScreenshot {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    /// Row pitch in `buffer`, rounded up to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+    padded_bytes_per_row: u32,
+    /// The surface stores blue first; PNG wants red first.
+    swap_red_blue: bool,
+}
+
+/// Record a copy of `texture` into a new readback buffer. The texture must allow `COPY_SRC`
+/// and use an 8-bit RGBA or BGRA format.
+pub fn encode_copy(device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) -> anyhow::Result<PendingScreenshot> {
+    if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+        bail!("the surface does not support copying frames");
+    }
+    let swap_red_blue = match texture.format() {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        format => bail!("unsupported surface format {format:?}"),
+    };
+
+    let width = texture.width();
+    let height = texture.height();
+    let padded_bytes_per_row = (width * BYTES_PER_PIXEL).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("screenshot_buffer"),
+        size: u64::from(padded_bytes_per_row) * u64::from(height),
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+
+    Ok(PendingScreenshot {
+        buffer,
+        width,
+        height,
+        padded_bytes_per_row,
+        swap_red_blue,
+    })
+}
+
+impl PendingScreenshot {
+    /// Wait for the copy to land, then write the frame to `path` as a PNG. Call after submitting the encoder.
+    pub fn save(self, device: &wgpu::Device, path: &Path) -> anyhow::Result<()> {
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::PollType::Wait).context("wait for screenshot copy")?;
+        receiver.recv().context("screenshot callback dropped")?.context("map screenshot buffer")?;
+
+        // Strip the row padding while repacking into a tightly packed RGBA image.
+        let row_bytes = (self.width * BYTES_PER_PIXEL) as usize;
+        let mut pixels = Vec::with_capacity(row_bytes * self.height as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks_exact(self.padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..row_bytes]);
+            }
+        }
+        self.buffer.unmap();
+
+        if self.swap_red_blue {
+            for pixel in pixels.chunks_exact_mut(BYTES_PER_PIXEL as usize) {
+                pixel.swap(0, 2);
+            }
+        }
+        // The frame is opaque; drop whatever the compositor alpha mode left behind.
+        for pixel in pixels.chunks_exact_mut(BYTES_PER_PIXEL as usize) {
+            pixel[3] = u8::MAX;
+        }
+
+        let image = image::RgbaImage::from_raw(self.width, self.height, pixels).context("screenshot size mismatch")?;
+        image.save(path).with_context(|| format!("write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// `screenshot-<unix millis>.png` in the working directory.
+pub fn timestamped_path() -> PathBuf {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis());
+    PathBuf::from(format!("screenshot-{millis}.png"))
+}
@@ -8,11 +8,13 @@ struct GridVertexInput {
 struct UiVertexInput {
     @location(0) position: vec2<f32>,
     @location(1) color: vec3<f32>,
+    @location(2) alpha: f32,
 };
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) color: vec3<f32>,
+    @location(1) alpha: f32,
 };
 
 @vertex
@@ -21,6 +23,7 @@ fn vs_grid(input: GridVertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.position = vec4<f32>(position, 0.0, 1.0);
     out.color = input.color;
+    out.alpha = 1.0;
     return out;
 }
 
@@ -29,10 +32,72 @@ fn vs_ui(input: UiVertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.position = vec4<f32>(input.position, 0.0, 1.0);
     out.color = input.color;
+    out.alpha = input.alpha;
     return out;
 }
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(input.color, 1.0);
+    return vec4<f32>(input.color, input.alpha);
+}
+
+// GPU-side simulation: cells are u32 (0 = dead, 1 = alive), ping-ponged between two storage buffers.
+
+struct SimParams {
+    width: u32,
+    height: u32,
+    origin: vec2<f32>,
+    cell_extent: vec2<f32>,
+    inset: f32,
+    _pad: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: SimParams;
+@group(0) @binding(1) var<storage, read> cells_in: array<u32>;
+@group(0) @binding(2) var<storage, read_write> cells_out: array<u32>;
+
+// Same as shared::colors::Classic::DEFAULT, which the CPU path draws with.
+const ALIVE_COLOR: vec3<f32> = vec3<f32>(0.95, 0.95, 0.95);
+const DEAD_COLOR: vec3<f32> = vec3<f32>(0.18, 0.18, 0.22);
+
+fn cell_index(row: i32, col: i32) -> u32 {
+    let height = i32(params.height);
+    let width = i32(params.width);
+    let wrapped_row = (row + height) % height;
+    let wrapped_col = (col + width) % width;
+    return u32(wrapped_row * width + wrapped_col);
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_step(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) {
+        return;
+    }
+    let row = i32(id.y);
+    let col = i32(id.x);
+    var neighbors = 0u;
+    for (var dr = -1; dr <= 1; dr++) {
+        for (var dc = -1; dc <= 1; dc++) {
+            if (dr != 0 || dc != 0) {
+                neighbors += cells_in[cell_index(row + dr, col + dc)];
+            }
+        }
+    }
+    let index = cell_index(row, col);
+    let alive = cells_in[index] != 0u;
+    cells_out[index] = select(0u, 1u, neighbors == 3u || (alive && neighbors == 2u));
+}
+
+@vertex
+fn vs_gpu_grid(@location(0) local_pos: vec2<f32>, @builtin(instance_index) index: u32) -> VertexOutput {
+    let col = index % params.width;
+    let row = index / params.width;
+    let top_left = params.origin + vec2<f32>(f32(col) * params.cell_extent.x, -f32(row) * params.cell_extent.y);
+    let size = vec2<f32>(params.cell_extent.x, -params.cell_extent.y);
+    var out: VertexOutput;
+    let inset_pos = params.inset + local_pos * (1.0 - 2.0 * params.inset);
+    out.position = vec4<f32>(top_left + inset_pos * size, 0.0, 1.0);
+    out.color = select(DEAD_COLOR, ALIVE_COLOR, cells_in[index] != 0u);
+    out.alpha = 1.0;
+    return out;
 }
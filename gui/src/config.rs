@@ -0,0 +1,89 @@
+//! Settings that can be tuned without recompiling, read from a TOML file passed with `--config`. Keys missing from
+//! the file keep their defaults, and command-line flags override the file.
+use crate::theme::Theme;
+use crate::{CELL_SIZE, DEFAULT_GRID_WIDTH, DEFAULT_STEP_INTERVAL, MIN_GRID_DIMENSION, STEP_INTERVAL_RANGE_MS};
+use eframe::egui::Color32;
+use serde::Deserialize;
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Number of columns on the board.
+    pub width: usize,
+    /// Number of rows on the board; 9/16 of the width when unset.
+    pub height: Option<usize>,
+    /// Edge length of one cell in points at the initial zoom.
+    pub cell_size: f32,
+    /// Time between generations the simulation starts with, in milliseconds.
+    pub step_interval_ms: u64,
+    /// Board colors as `[r, g, b]` from 0 to 255; each one that is set replaces the saved theme's color at startup.
+    pub alive_color: Option<[u8; 3]>,
+    pub dead_color: Option<[u8; 3]>,
+    pub background_color: Option<[u8; 3]>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            width: DEFAULT_GRID_WIDTH,
+            height: None,
+            cell_size: CELL_SIZE,
+            step_interval_ms: DEFAULT_STEP_INTERVAL.as_millis() as u64,
+            alive_color: None,
+            dead_color: None,
+            background_color: None,
+        }
+    }
+}
+
+impl Config {
+    /// Read and validate the file at `path`.
+    pub fn load(path: &Path) -> Result<Config, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)?;
+        let config: Config = toml::from_str(&text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check the values the type alone doesn't rule out, naming the key at fault.
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, dimension) in [("width", Some(self.width)), ("height", self.height)] {
+            if let Some(dimension) = dimension.filter(|dimension| *dimension < MIN_GRID_DIMENSION) {
+                return Err(format!("`{key}` must be at least {MIN_GRID_DIMENSION}, got {dimension}"));
+            }
+        }
+        if !self.cell_size.is_finite() || self.cell_size <= 0.0 {
+            return Err(format!("`cell_size` must be a positive number, got {}", self.cell_size));
+        }
+        if !STEP_INTERVAL_RANGE_MS.contains(&self.step_interval_ms) {
+            return Err(format!(
+                "`step_interval_ms` must be between {} and {}, got {}",
+                STEP_INTERVAL_RANGE_MS.start(),
+                STEP_INTERVAL_RANGE_MS.end(),
+                self.step_interval_ms
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn grid_size(&self) -> (usize, usize) {
+        let height = self.height.unwrap_or((self.width * 9 / 16).max(MIN_GRID_DIMENSION));
+        (self.width, height)
+    }
+
+    pub fn step_interval(&self) -> Duration {
+        Duration::from_millis(self.step_interval_ms)
+    }
+
+    /// `theme` with the colors set in the file.
+    pub fn apply_colors(&self, mut theme: Theme) -> Theme {
+        let rgb = |[r, g, b]: [u8; 3]| Color32::from_rgb(r, g, b);
+        theme.alive = self.alive_color.map_or(theme.alive, rgb);
+        theme.dead = self.dead_color.map_or(theme.dead, rgb);
+        theme.background = self.background_color.map_or(theme.background, rgb);
+        theme
+    }
+}
@@ -1,20 +1,138 @@
+use clap::Parser;
+use config::Config;
 use eframe::egui;
 use eframe::egui::{ScrollArea, Ui};
+#[cfg(not(target_arch = "wasm32"))]
 use eframe::run_native;
-use shared::grid::CellState::Alive;
-use shared::grid::Grid;
+use egui_plot::{Line, Plot, PlotPoints};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use session::{Session, SessionRef};
+use shared::colors::ColorScheme;
+use shared::grid::CellState::{self, Alive, Dead};
+use shared::grid::{EdgeMode, Grid, Neighborhood};
+use shared::patterns::{self, Pattern};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+#[cfg(not(target_arch = "wasm32"))]
 use std::thread;
 use std::time::Duration;
+use theme::Theme;
 
-const GRID_WIDTH: usize = 200;
-const GRID_HEIGHT: usize = GRID_WIDTH * 9 / 16;
+mod config;
+mod session;
+mod theme;
+
+const DEFAULT_GRID_WIDTH: usize = 200;
+/// Smallest board edge accepted on the command line; the neighbor counting assumes at least 3x3.
+const MIN_GRID_DIMENSION: usize = 3;
 const CELL_SIZE: f32 = 8.0;
-const SLEEP_DURATION: Duration = Duration::from_millis(50);
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 20.0;
+const DEFAULT_STEP_INTERVAL: Duration = Duration::from_millis(50);
+const STEP_INTERVAL_RANGE_MS: std::ops::RangeInclusive<u64> = 1..=1000;
+const DEFAULT_PATTERN_PATH: &str = "pattern.rle";
+/// Neighbor counts are only written into cells at least this many points wide.
+const NEIGHBOR_COUNT_MIN_CELL_SIZE: f32 = 14.0;
+/// Key of the board theme in eframe's persisted storage.
+const THEME_STORAGE_KEY: &str = "theme";
+/// Key of the board, speed and view settings in eframe's persisted storage.
+const SESSION_STORAGE_KEY: &str = "session";
+/// Empty cells between the demo glider gun and the top-left corner of the board.
+const DEMO_GUN_MARGIN: usize = 1;
+/// Tint of dead cells that the next generation brings to life, in preview mode.
+const BIRTH_PREVIEW_COLOR: egui::Color32 = egui::Color32::from_rgb(60, 200, 90);
+/// Tint of alive cells that die in the next generation, in preview mode.
+const DEATH_PREVIEW_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 70, 60);
+/// How far preview mode pulls a cell's color towards its tint.
+const PREVIEW_TINT: f32 = 0.7;
+/// Cells from the cursor to the edge of the randomize brush, which Shift-dragging paints with.
+const DEFAULT_BRUSH_RADIUS: usize = 3;
+const MAX_BRUSH_RADIUS: usize = 30;
+/// Chance of each cell under the randomize brush coming out alive.
+const DEFAULT_BRUSH_DENSITY: f64 = 0.5;
+/// Generations the population chart looks back over.
+const POPULATION_HISTORY_LEN: usize = 500;
+/// Frames the FPS chart looks back over.
+const FPS_HISTORY_LEN: usize = 300;
+const CHART_SIZE: egui::Vec2 = egui::vec2(400.0, 80.0);
+
+/// Conway's Game of Life in an egui window.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// TOML file with the board size, step interval and colors; the flags below take precedence
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Number of columns on the board [default: 200]
+    #[arg(long, value_parser = parse_dimension)]
+    width: Option<usize>,
+
+    /// Number of rows on the board [default: 9/16 of the width]
+    #[arg(long, value_parser = parse_dimension)]
+    height: Option<usize>,
+
+    /// Edge length of one cell in points at the initial zoom [default: 8]
+    #[arg(long, value_parser = parse_cell_size)]
+    cell_size: Option<f32>,
+}
 
+impl Args {
+    /// The `--config` file, or the defaults without one, with the size flags given on the command line applied.
+    fn config(&self) -> Result<Config, String> {
+        let mut config = match &self.config {
+            Some(path) => Config::load(path).map_err(|err| format!("could not load {}: {err}", path.display()))?,
+            None => Config::default(),
+        };
+        config.width = self.width.unwrap_or(config.width);
+        config.height = self.height.or(config.height);
+        config.cell_size = self.cell_size.unwrap_or(config.cell_size);
+        Ok(config)
+    }
+}
+
+/// Simulation state shared between the UI thread and the background updater.
+///
+/// Whoever changes `grid` makes sure the change gets painted: the background thread requests a repaint after every
+/// generation that changed something, the `GuiOfLife` helpers that edit the grid request one themselves, and the
+/// drawing code in `create_grid` edits the grid before painting it in the same frame. On the web there is no
+/// background thread and `GuiOfLife::advance_when_due` advances the grid from `update` instead.
+#[derive(Default)]
+struct SimulationState {
+    grid: Grid,
+    running: bool,
+    step_interval: Duration,
+}
+
+impl SimulationState {
+    /// A running simulation of an empty board of the configured size and speed.
+    fn new(config: &Config) -> Self {
+        let (width, height) = config.grid_size();
+        let mut grid = Grid::new(width, height);
+        grid.set_population_history(POPULATION_HISTORY_LEN);
+        SimulationState {
+            grid,
+            running: true,
+            step_interval: config.step_interval(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() {
+    let config = match Args::parse().config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(2);
+        }
+    };
+
     // Shared grid state wrapped in Arc<Mutex<T>> for synchronization between threads
-    let shared_grid = Arc::new(Mutex::new((Grid::new(GRID_WIDTH, GRID_HEIGHT), false)));
+    let shared_grid = Arc::new(Mutex::new(SimulationState::new(&config)));
 
     run_native(
         "Game of Life GUI",
@@ -24,86 +142,734 @@ fn main() {
             let ctx = cc.egui_ctx.clone();
             let grid_clone = Arc::clone(&shared_grid);
 
-            // Spawn a background thread to update the grid
+            // Spawn a background thread to update the grid. The lock is only taken after sleeping
+            // and released at the end of each iteration so the UI thread is never blocked by the sleep.
             thread::spawn(move || loop {
-                thread::sleep(SLEEP_DURATION);
-                let mut grid_and_state = grid_clone.lock().unwrap();
-                let changed = grid_and_state.0.advance();
-                grid_and_state.1 = changed; // Mark the grid as dirty
-                if changed {
+                let step_interval = grid_clone.lock().unwrap().step_interval;
+                thread::sleep(step_interval);
+                let mut state = grid_clone.lock().unwrap();
+                if !state.running {
+                    continue;
+                }
+                if state.grid.advance() {
                     ctx.request_repaint();
                 }
             });
 
-            Ok(Box::new(GuiOfLife::new(cc, shared_grid)))
+            Ok(Box::new(GuiOfLife::new(cc, shared_grid, &config)))
         }),
     )
     .unwrap();
 }
 
+/// Web entry point: runs the app in the page's `the_canvas_id` canvas.
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use eframe::wasm_bindgen::JsCast;
+
+    // The browser has no command line or config file, so the app starts from the defaults.
+    let config = Args::parse_from([env!("CARGO_PKG_NAME")]).config().unwrap_or_default();
+    let shared_grid = Arc::new(Mutex::new(SimulationState::new(&config)));
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let canvas = eframe::web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("the_canvas_id"))
+            .and_then(|element| element.dyn_into::<eframe::web_sys::HtmlCanvasElement>().ok())
+            .expect("page has no canvas with id the_canvas_id");
+        eframe::WebRunner::new()
+            .start(
+                canvas,
+                eframe::WebOptions::default(),
+                Box::new(move |cc| Ok(Box::new(GuiOfLife::new(cc, shared_grid, &config)))),
+            )
+            .await
+            .expect("failed to start eframe");
+    });
+}
+
+/// Neighborhoods offered in the picker, in display order.
+const NEIGHBORHOODS: [(&str, Neighborhood); 3] = [
+    ("Moore", Neighborhood::Moore),
+    ("Von Neumann", Neighborhood::VonNeumann),
+    ("Hex", Neighborhood::Hex),
+];
+
+/// Outline of each cell on the board.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum CellShape {
+    /// Squares with corners rounded by a quarter of the cell size.
+    #[default]
+    Rounded,
+    Square,
+    Circle,
+}
+
+impl CellShape {
+    const ALL: [CellShape; 3] = [CellShape::Rounded, CellShape::Square, CellShape::Circle];
+
+    fn name(self) -> &'static str {
+        match self {
+            CellShape::Rounded => "Rounded",
+            CellShape::Square => "Square",
+            CellShape::Circle => "Circle",
+        }
+    }
+}
+
+/// What dragging over the board does to the cells under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stroke {
+    Paint(CellState),
+    /// Randomize the disc of the brush radius around each cell.
+    Randomize,
+}
+
 #[derive(Default)]
 struct GuiOfLife {
-    grid_and_state: Arc<Mutex<(Grid, bool)>>, // Shared grid state
+    state: Arc<Mutex<SimulationState>>,   // Shared grid state
+    zoom: f32,                            // Multiplier for CELL_SIZE
+    last_painted: Option<(usize, usize)>, // Last cell touched by the current drag
+    pattern_path: String,                 // File used by Save and Load
+    status: String,                       // Outcome of the last file operation
+    theme: Theme,                         // Board colors, persisted between runs
+    show_neighbor_counts: bool,           // Write each cell's alive-neighbor count into it
+    cell_shape: CellShape,                // How each cell is drawn
+    preview: bool,                        // Tint cells that change in the next generation
+    brush_radius: usize,                  // Reach of the Shift-drag randomize brush, in cells
+    brush_density: f64,                   // Share of the cells under the brush it makes alive
+    fps_history: VecDeque<f32>,           // Frames per second of the recent frames, only sampled while the charts are open
+    #[cfg(target_arch = "wasm32")]
+    last_step_time: f64, // egui time of the last generation, in seconds
 }
 
 impl GuiOfLife {
-    fn new(_cc: &eframe::CreationContext<'_>, shared_grid: Arc<Mutex<(Grid, bool)>>) -> Self {
-        Self { grid_and_state: shared_grid }
+    fn new(cc: &eframe::CreationContext<'_>, shared_grid: Arc<Mutex<SimulationState>>, config: &Config) -> Self {
+        let theme: Theme = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, THEME_STORAGE_KEY))
+            .unwrap_or_default();
+        let theme = config.apply_colors(theme);
+        cc.egui_ctx.set_theme(theme.mode);
+        let session: Option<Session> = cc.storage.and_then(|storage| eframe::get_value(storage, SESSION_STORAGE_KEY));
+        let mut app = Self {
+            state: shared_grid,
+            zoom: (config.cell_size / CELL_SIZE).clamp(MIN_ZOOM, MAX_ZOOM),
+            last_painted: None,
+            pattern_path: DEFAULT_PATTERN_PATH.to_string(),
+            status: String::new(),
+            theme,
+            show_neighbor_counts: false,
+            cell_shape: CellShape::default(),
+            preview: false,
+            brush_radius: DEFAULT_BRUSH_RADIUS,
+            brush_density: DEFAULT_BRUSH_DENSITY,
+            fps_history: VecDeque::with_capacity(FPS_HISTORY_LEN),
+            #[cfg(target_arch = "wasm32")]
+            last_step_time: 0.0,
+        };
+        if let Some(session) = session {
+            app.restore_session(session);
+        }
+        app
+    }
+
+    /// Pick up where the previous run left off. The board keeps the size set on the command line or in the config
+    /// file: a board saved at another size is cropped, or padded with dead cells, to fit it.
+    fn restore_session(&mut self, session: Session) {
+        let mut state = self.state.lock().unwrap();
+        let (width, height) = (state.grid.width(), state.grid.height());
+        let mut grid = session.grid;
+        if (grid.width(), grid.height()) != (width, height) {
+            grid.resize(width, height);
+        }
+        grid.set_population_history(POPULATION_HISTORY_LEN);
+        state.grid = grid;
+        state.running = session.running;
+        let step_interval_ms = session
+            .step_interval_ms
+            .clamp(*STEP_INTERVAL_RANGE_MS.start(), *STEP_INTERVAL_RANGE_MS.end());
+        state.step_interval = Duration::from_millis(step_interval_ms);
+        drop(state);
+
+        if session.zoom.is_finite() {
+            self.zoom = session.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        }
+        self.pattern_path = session.pattern_path;
+        self.show_neighbor_counts = session.show_neighbor_counts;
+        self.cell_shape = session.cell_shape;
+        self.preview = session.preview;
+    }
+
+    fn set_theme(&mut self, ctx: &egui::Context, theme: Theme) {
+        if theme.mode != self.theme.mode {
+            ctx.set_theme(theme.mode);
+        }
+        self.theme = theme;
+    }
+
+    /// Preset picker plus color buttons; editing a color turns the theme into a custom one.
+    fn theme_controls(&mut self, ui: &mut Ui) {
+        ui.label("Theme");
+        let mut selected = self.theme;
+        egui::ComboBox::from_id_salt("theme").selected_text(self.theme.name()).show_ui(ui, |ui| {
+            for (name, preset) in Theme::PRESETS {
+                ui.selectable_value(&mut selected, preset, name);
+            }
+        });
+        ui.color_edit_button_srgba(&mut selected.alive).on_hover_text("Alive");
+        ui.color_edit_button_srgba(&mut selected.dead).on_hover_text("Dead");
+        ui.color_edit_button_srgba(&mut selected.background).on_hover_text("Background");
+        if selected != self.theme {
+            self.set_theme(ui.ctx(), selected);
+        }
+    }
+
+    fn randomize(&mut self, ctx: &egui::Context) {
+        self.state.lock().unwrap().grid.randomize();
+        ctx.request_repaint();
+    }
+
+    fn clear(&mut self, ctx: &egui::Context) {
+        let mut state = self.state.lock().unwrap();
+        state.grid.clear();
+        ctx.request_repaint();
+    }
+
+    /// Generation and population, read under a short-lived lock.
+    fn stats(&self) -> (u64, usize) {
+        let state = self.state.lock().unwrap();
+        (state.grid.generation(), state.grid.population())
+    }
+
+    fn is_running(&self) -> bool {
+        self.state.lock().unwrap().running
+    }
+
+    fn toggle_running(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.running = !state.running;
+    }
+
+    fn wraps_edges(&self) -> bool {
+        self.state.lock().unwrap().grid.edge_modes() == (EdgeMode::Toroidal, EdgeMode::Toroidal)
+    }
+
+    /// Takes effect with the next generation; the board itself is left as it is.
+    fn set_wraps_edges(&mut self, ctx: &egui::Context, wraps: bool) {
+        let edge_mode = if wraps { EdgeMode::Toroidal } else { EdgeMode::Dead };
+        self.state.lock().unwrap().grid.set_edge_mode(edge_mode);
+        ctx.request_repaint();
+    }
+
+    fn neighborhood(&self) -> Neighborhood {
+        self.state.lock().unwrap().grid.neighborhood()
+    }
+
+    /// Takes effect with the next generation; a hex board is drawn with its odd rows shifted right right away.
+    fn set_neighborhood(&mut self, ctx: &egui::Context, neighborhood: Neighborhood) {
+        self.state.lock().unwrap().grid.set_neighborhood(neighborhood);
+        ctx.request_repaint();
+    }
+
+    fn step_interval_ms(&self) -> u64 {
+        self.state.lock().unwrap().step_interval.as_millis() as u64
+    }
+
+    fn set_step_interval_ms(&mut self, millis: u64) {
+        self.state.lock().unwrap().step_interval = Duration::from_millis(millis);
+    }
+
+    /// Stands in for the background thread on the web, where threads aren't available: advance the grid once the step
+    /// interval has passed since the last generation, and schedule a repaint for when the next one is due.
+    #[cfg(target_arch = "wasm32")]
+    fn advance_when_due(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|input| input.time);
+        let mut state = self.state.lock().unwrap();
+        if !state.running {
+            return;
+        }
+        let step_interval = state.step_interval.as_secs_f64();
+        if now - self.last_step_time >= step_interval {
+            self.last_step_time = now;
+            state.grid.advance();
+        }
+        let until_next = (self.last_step_time + step_interval - now).max(0.0);
+        ctx.request_repaint_after(Duration::from_secs_f64(until_next));
     }
 
-    fn randomize(&mut self) {
-        let mut grid = self.grid_and_state.lock().unwrap();
-        grid.0.randomize();
-        grid.1 = true;
+    fn step(&mut self, ctx: &egui::Context) {
+        let mut state = self.state.lock().unwrap();
+        state.grid.advance();
+        ctx.request_repaint();
+    }
+
+    fn save_to_file(&mut self) {
+        let rle = self.state.lock().unwrap().grid.to_rle();
+        self.status = match std::fs::write(&self.pattern_path, rle) {
+            Ok(()) => format!("Saved {}", self.pattern_path),
+            Err(err) => format!("Could not save {}: {err}", self.pattern_path),
+        };
+    }
+
+    fn load_from_file(&mut self, ctx: &egui::Context) {
+        self.status = match read_pattern(Path::new(&self.pattern_path)) {
+            Ok(pattern) => {
+                let grown = self.place_pattern(ctx, &pattern);
+                format!("Loaded {}{}", self.pattern_path, grown_note(grown))
+            }
+            Err(err) => format!("Could not load {}: {err}", self.pattern_path),
+        };
+    }
+
+    fn place_library_pattern(&mut self, ctx: &egui::Context, pattern: &Pattern) {
+        let grown = self.place_pattern(ctx, &pattern.grid());
+        self.status = format!("Placed {}{}", pattern.name, grown_note(grown));
+    }
+
+    /// One-key classroom demo: a Gosper glider gun in the top-left corner of a cleared board, running right away.
+    fn show_glider_gun(&mut self, ctx: &egui::Context) {
+        let gun = patterns::GOSPER_GLIDER_GUN.grid();
+        let mut state = self.state.lock().unwrap();
+        let width = state.grid.width().max(gun.width() + DEMO_GUN_MARGIN);
+        let height = state.grid.height().max(gun.height() + DEMO_GUN_MARGIN);
+        let grown = width > state.grid.width() || height > state.grid.height();
+
+        state.grid.clear();
+        state.grid.resize(width, height);
+        state.grid.set_rule(gun.rule());
+        state.grid.stamp(&gun, DEMO_GUN_MARGIN, DEMO_GUN_MARGIN);
+        state.running = true;
+        drop(state);
+
+        self.status = format!("Placed {}{}", patterns::GOSPER_GLIDER_GUN.name, grown_note(grown));
+        ctx.request_repaint();
+    }
+
+    /// Replace the board with `pattern` centered on it, growing the board when the pattern doesn't fit. The board
+    /// switches to the pattern's rule. Returns whether the board had to grow.
+    fn place_pattern(&mut self, ctx: &egui::Context, pattern: &Grid) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let pattern_width = pattern.width();
+        let pattern_height = pattern.height();
+        let grown = pattern_width > state.grid.width() || pattern_height > state.grid.height();
+        let width = state.grid.width().max(pattern_width);
+        let height = state.grid.height().max(pattern_height);
+
+        state.grid.clear();
+        state.grid.resize(width, height);
+        state.grid.set_rule(pattern.rule());
+        state.grid.stamp(pattern, (height - pattern_height) / 2, (width - pattern_width) / 2);
+        ctx.request_repaint();
+        grown
+    }
+
+    /// Frames per second and population over the recent past, each with its range written above it. Frames are only
+    /// timed while this runs, so closed charts cost nothing.
+    fn charts(&mut self, ui: &mut Ui) {
+        let frame_time = ui.input(|input| input.unstable_dt);
+        if frame_time > 0.0 {
+            if self.fps_history.len() == FPS_HISTORY_LEN {
+                self.fps_history.pop_front();
+            }
+            self.fps_history.push_back(1.0 / frame_time);
+        }
+        let fps = || self.fps_history.iter().copied();
+        ui.label(match sample_range(fps()) {
+            Some((min, max)) => format!("FPS over the last {} frames: {min:.0} to {max:.0}", self.fps_history.len()),
+            None => "FPS: waiting for the next frame".to_string(),
+        });
+        line_chart(ui, "FPS", fps(), self.theme);
+
+        let state = self.state.lock().unwrap();
+        let history = state.grid.population_history();
+        let population = || history.iter().map(|&population| population as f32);
+        ui.label(match sample_range(population()) {
+            Some((min, max)) => format!("Population over the last {} generations: {min} to {max}", history.len()),
+            None => "Population: waiting for the next generation".to_string(),
+        });
+        line_chart(ui, "Population", population(), self.theme);
+    }
+
+    /// Edge length of one cell in points. The zoom is clamped here too so a default-constructed app stays drawable.
+    fn cell_size(&self) -> f32 {
+        CELL_SIZE * self.zoom.clamp(MIN_ZOOM, MAX_ZOOM)
     }
 
     fn create_grid(&mut self, ui: &mut Ui) {
-        let grid_and_state = self.grid_and_state.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
+        let width = state.grid.width();
+        let height = state.grid.height();
+        let cell_size = self.cell_size();
+        // Hex boards shift their odd rows half a cell to the right, like the lattice they stand for
+        let hex = state.grid.neighborhood() == Neighborhood::Hex;
+        let hex_width = if hex && height > 1 { cell_size * 0.5 } else { 0.0 };
 
         // Calculate the grid starting point
-        let (rect_min, _) = ui.allocate_exact_size(
-            egui::vec2(
-                CELL_SIZE * grid_and_state.0.cells[0].len() as f32,
-                CELL_SIZE * grid_and_state.0.cells.len() as f32,
-            ),
-            egui::Sense::hover(),
+        let (grid_rect, response) = ui.allocate_exact_size(
+            egui::vec2(cell_size * width as f32 + hex_width, cell_size * height as f32),
+            egui::Sense::click_and_drag(),
         );
+        ui.painter().rect_filled(grid_rect, 0.0, self.theme.background);
+
+        // Ctrl+scroll over the grid zooms; the surrounding ScrollArea keeps handling plain scrolling
+        if response.hovered() {
+            let zoom_delta = ui.input(|input| input.zoom_delta());
+            if zoom_delta != 1.0 {
+                self.zoom = (self.zoom * zoom_delta).clamp(MIN_ZOOM, MAX_ZOOM);
+            }
+        }
+
+        // Toggle the clicked cell; this works whether or not the simulation is running
+        if response.clicked() {
+            if let Some((row, col)) = response
+                .interact_pointer_pos()
+                .and_then(|pos| cell_at(grid_rect, pos, cell_size, width, height, hex))
+            {
+                state.grid.toggle(row, col);
+            }
+        }
+
+        // Dragging paints with the left button and erases with the right one; holding Shift turns the left button
+        // into the randomize brush
+        let stroke = if response.dragged_by(egui::PointerButton::Primary) {
+            if ui.input(|input| input.modifiers.shift) {
+                Some(Stroke::Randomize)
+            } else {
+                Some(Stroke::Paint(Alive))
+            }
+        } else if response.dragged_by(egui::PointerButton::Secondary) {
+            Some(Stroke::Paint(Dead))
+        } else {
+            None
+        };
+        let hovered_cell = response
+            .interact_pointer_pos()
+            .and_then(|pos| cell_at(grid_rect, pos, cell_size, width, height, hex));
+        match (stroke, hovered_cell) {
+            (Some(stroke), Some(cell)) if self.last_painted != Some(cell) => {
+                // Start from where the button went down so the first cell isn't lost to the drag threshold,
+                // and fill in the cells between pointer samples when the mouse moves quickly.
+                let press_origin = ui.input(|input| input.pointer.press_origin());
+                let start = self
+                    .last_painted
+                    .or_else(|| press_origin.and_then(|pos| cell_at(grid_rect, pos, cell_size, width, height, hex)))
+                    .unwrap_or(cell);
+                let mut rng = rand::rng();
+                for (row, col) in cells_between(start, cell) {
+                    match stroke {
+                        Stroke::Paint(paint) => state.grid.set(row, col, paint),
+                        Stroke::Randomize => randomize_disc(&mut state.grid, (row, col), self.brush_radius, self.brush_density, &mut rng),
+                    }
+                }
+                self.last_painted = Some(cell);
+            }
+            (Some(_), Some(_)) => {}
+            _ => self.last_painted = None,
+        }
+
+        // Counts are computed once per frame, and only when they are large enough to read or the preview needs them
+        let show_counts = self.show_neighbor_counts && cell_size >= NEIGHBOR_COUNT_MIN_CELL_SIZE;
+        let neighbor_counts = (show_counts || self.preview).then(|| state.grid.neighbor_counts());
+        let rule = state.grid.rule();
+        let scheme = self.theme.color_scheme();
 
         // Draw each cell at its calculated position
-        for (row_index, row) in grid_and_state.0.cells.iter().enumerate() {
+        for (row_index, row) in state.grid.cells.iter().enumerate() {
             for (col_index, cell) in row.iter().enumerate() {
                 // Determine the position of the top-left corner of the cell
-                let pos = rect_min.min + egui::vec2(col_index as f32 * CELL_SIZE, row_index as f32 * CELL_SIZE);
+                let shift = if hex { row_shift(row_index, cell_size) } else { 0.0 };
+                let pos = grid_rect.min + egui::vec2(col_index as f32 * cell_size + shift, row_index as f32 * cell_size);
 
-                // Determine the color for the cell
-                let color = if *cell == Alive {
-                    egui::Color32::WHITE
-                } else {
-                    egui::Color32::DARK_GRAY
-                };
+                // Determine the color for the cell; dying stages fade from blue towards the dead color
+                let mut color = theme::to_color32(scheme.color(*cell, None, rule.states()));
 
-                // Draw the cell as a filled rectangle
+                // Preview mode tints the cells the next generation flips, without advancing the grid
+                if let Some(counts) = neighbor_counts.as_ref().filter(|_| self.preview) {
+                    let alive_neighbors = usize::from(counts[row_index][col_index]);
+                    match *cell {
+                        Alive if !rule.survives_on(alive_neighbors) => color = color.lerp_to_gamma(DEATH_PREVIEW_COLOR, PREVIEW_TINT),
+                        Dead if rule.births_on(alive_neighbors) => color = color.lerp_to_gamma(BIRTH_PREVIEW_COLOR, PREVIEW_TINT),
+                        _ => {}
+                    }
+                }
+
+                // Draw the cell in the selected shape
                 let painter = ui.painter(); // Get the painter for the UI
-                let rect = egui::Rect::from_min_size(pos, egui::vec2(CELL_SIZE, CELL_SIZE));
-                painter.rect_filled(rect, CELL_SIZE / 4f32, color);
+                let rect = egui::Rect::from_min_size(pos, egui::vec2(cell_size, cell_size));
+                match self.cell_shape {
+                    CellShape::Rounded => painter.rect_filled(rect, cell_size / 4f32, color),
+                    CellShape::Square => painter.rect_filled(rect, 0.0, color),
+                    CellShape::Circle => painter.circle_filled(rect.center(), cell_size / 2f32, color),
+                };
+
+                // Write the count in the opposite color so it stays readable on alive and dead cells alike
+                if let Some(counts) = neighbor_counts.as_ref().filter(|_| show_counts) {
+                    let text_color = if *cell == Alive { self.theme.dead } else { self.theme.alive };
+                    painter.text(
+                        rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        counts[row_index][col_index],
+                        egui::FontId::monospace(cell_size * 0.6),
+                        text_color,
+                    );
+                }
             }
         }
     }
 }
 
+/// Swatches explaining the preview mode tints.
+fn preview_legend(ui: &mut Ui) {
+    for (color, label) in [(BIRTH_PREVIEW_COLOR, "Born next step"), (DEATH_PREVIEW_COLOR, "Dies next step")] {
+        let (swatch, _) = ui.allocate_exact_size(egui::vec2(CELL_SIZE * 1.5, CELL_SIZE * 1.5), egui::Sense::hover());
+        ui.painter().rect_filled(swatch, 2.0, color);
+        ui.label(label);
+    }
+}
+
+/// Smallest and largest of `samples`, or `None` when there are none.
+fn sample_range(mut samples: impl Iterator<Item = f32>) -> Option<(f32, f32)> {
+    let first = samples.next()?;
+    Some(samples.fold((first, first), |(min, max), sample| (min.min(sample), max.max(sample))))
+}
+
+/// Line chart of `samples`, oldest first, scaled to fit. The view is fixed, so dragging or scrolling over the chart
+/// doesn't pan it away from the latest samples.
+fn line_chart(ui: &mut Ui, name: &str, samples: impl Iterator<Item = f32>, theme: Theme) {
+    let points: PlotPoints = samples.enumerate().map(|(index, sample)| [index as f64, f64::from(sample)]).collect();
+    Plot::new(name)
+        .width(CHART_SIZE.x)
+        .height(CHART_SIZE.y)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .allow_double_click_reset(false)
+        .show(ui, |plot_ui| plot_ui.line(Line::new(name, points).color(theme.alive)));
+}
+
+/// Map a pointer position to the `(row, col)` under it. Positions in the rounded-off corners of a cell still
+/// belong to that cell; anything outside the grid rectangle, or beside a shifted `hex` row, yields `None`.
+fn cell_at(grid_rect: egui::Rect, pos: egui::Pos2, cell_size: f32, width: usize, height: usize, hex: bool) -> Option<(usize, usize)> {
+    let offset = pos - grid_rect.min;
+    if offset.y < 0.0 {
+        return None;
+    }
+    let row = (offset.y / cell_size) as usize;
+    let x = offset.x - if hex { row_shift(row, cell_size) } else { 0.0 };
+    if x < 0.0 {
+        return None;
+    }
+    let col = (x / cell_size) as usize;
+    (row < height && col < width).then_some((row, col))
+}
+
+/// How far right row `row` of a hex board is drawn: half a cell on odd rows.
+fn row_shift(row: usize, cell_size: f32) -> f32 {
+    if row.is_multiple_of(2) {
+        0.0
+    } else {
+        cell_size * 0.5
+    }
+}
+
+/// Status suffix for patterns that didn't fit the board.
+fn grown_note(grown: bool) -> &'static str {
+    if grown {
+        " (board enlarged to fit)"
+    } else {
+        ""
+    }
+}
+
+/// Read a pattern file, choosing the parser by extension: `.cells` is plaintext, anything else RLE.
+fn read_pattern(path: &Path) -> Result<Grid, Box<dyn Error>> {
+    let text = std::fs::read_to_string(path)?;
+    let is_plaintext = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("cells"));
+    Ok(if is_plaintext {
+        Grid::from_plaintext(&text)?
+    } else {
+        Grid::from_rle(&text)?
+    })
+}
+
+/// Randomize the cells at most `radius` cells from `center`, one row of the disc at a time. Parts of the disc beyond
+/// the edges of the board are dropped.
+fn randomize_disc(grid: &mut Grid, center: (usize, usize), radius: usize, density: f64, rng: &mut impl Rng) {
+    let (center_row, center_col) = center;
+    for offset in 0..=2 * radius {
+        let Some(row) = (center_row + offset).checked_sub(radius) else {
+            continue;
+        };
+        let row_distance = offset.abs_diff(radius);
+        let half_width = ((radius * radius - row_distance * row_distance) as f64).sqrt() as usize;
+        let left = center_col.saturating_sub(half_width);
+        grid.randomize_region(row, left, 1, center_col + half_width + 1 - left, density, rng);
+    }
+}
+
+/// Cells on the straight line from `from` to `to`, both included.
+fn cells_between(from: (usize, usize), to: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    let (row_delta, col_delta) = (to.0 as f32 - from.0 as f32, to.1 as f32 - from.1 as f32);
+    let steps = row_delta.abs().max(col_delta.abs()) as usize;
+    (0..=steps).map(move |step| {
+        let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+        (
+            (from.0 as f32 + row_delta * t).round() as usize,
+            (from.1 as f32 + col_delta * t).round() as usize,
+        )
+    })
+}
+
+/// Parse a board edge length for `--width`/`--height`.
+fn parse_dimension(value: &str) -> Result<usize, String> {
+    let dimension: usize = value.parse().map_err(|err| format!("`{value}` is not a whole number: {err}"))?;
+    if dimension < MIN_GRID_DIMENSION {
+        return Err(format!("must be at least {MIN_GRID_DIMENSION}, got {dimension}"));
+    }
+    Ok(dimension)
+}
+
+/// Parse `--cell-size`, which must be a positive number of points.
+fn parse_cell_size(value: &str) -> Result<f32, String> {
+    let size: f32 = value.parse().map_err(|err| format!("`{value}` is not a number: {err}"))?;
+    if !size.is_finite() || size <= 0.0 {
+        return Err(format!("must be a positive number, got {value}"));
+    }
+    Ok(size)
+}
+
 impl eframe::App for GuiOfLife {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        #[cfg(target_arch = "wasm32")]
+        self.advance_when_due(ctx);
+        // A dropped file is loaded as if its path had been typed into the pattern field.
+        let dropped_paths: Vec<_> = ctx.input(|input| input.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect());
+        for path in dropped_paths {
+            self.pattern_path = path.display().to_string();
+            self.load_from_file(ctx);
+        }
+        // Keys are left alone while a text field has focus, so typing a path doesn't trigger them.
+        if !ctx.wants_keyboard_input() && ctx.input(|input| input.key_pressed(egui::Key::Num1)) {
+            self.show_glider_gun(ctx);
+        }
         egui::CentralPanel::default().show(ctx, |ui| {
             ScrollArea::both().show(ui, |ui| {
                 ui.heading("Game of Life");
+                let (generation, population) = self.stats();
+                ui.label(format!("Generation {generation} · {population} alive"));
                 ui.horizontal(|ui| {
                     if ui.button("Randomize").clicked() {
-                        self.randomize();
+                        self.randomize(ui.ctx());
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.clear(ui.ctx());
+                    }
+                    let running = self.is_running();
+                    let label = if running { "Pause" } else { "Resume" };
+                    if ui.button(label).clicked() {
+                        self.toggle_running();
+                    }
+                    // Stepping only makes sense while the background thread is not advancing the grid
+                    if ui.add_enabled(!running, egui::Button::new("Step")).clicked() {
+                        self.step(ui.ctx());
+                    }
+
+                    let mut step_interval_ms = self.step_interval_ms();
+                    let slider = egui::Slider::new(&mut step_interval_ms, STEP_INTERVAL_RANGE_MS)
+                        .logarithmic(true)
+                        .suffix(" ms")
+                        .text("Step interval");
+                    if ui.add(slider).changed() {
+                        self.set_step_interval_ms(step_interval_ms);
+                    }
+
+                    ui.add(egui::Slider::new(&mut self.zoom, MIN_ZOOM..=MAX_ZOOM).logarithmic(true).text("Zoom"));
+                    let mut wraps = self.wraps_edges();
+                    if ui.checkbox(&mut wraps, "Wrap edges").changed() {
+                        self.set_wraps_edges(ui.ctx(), wraps);
+                    }
+                    let mut neighborhood = self.neighborhood();
+                    let selected = NEIGHBORHOODS.iter().find(|(_, option)| *option == neighborhood);
+                    ui.label("Neighborhood");
+                    egui::ComboBox::from_id_salt("neighborhood")
+                        .selected_text(selected.map_or("", |(name, _)| *name))
+                        .show_ui(ui, |ui| {
+                            for (name, option) in NEIGHBORHOODS {
+                                ui.selectable_value(&mut neighborhood, option, name);
+                            }
+                        });
+                    if neighborhood != self.neighborhood() {
+                        self.set_neighborhood(ui.ctx(), neighborhood);
                     }
+                    ui.checkbox(&mut self.show_neighbor_counts, "Neighbor counts")
+                        .on_hover_text("Show each cell's alive neighbors once cells are large enough");
+                    ui.checkbox(&mut self.preview, "Preview")
+                        .on_hover_text("Tint the cells that change in the next generation");
+                });
+                if self.preview {
+                    ui.horizontal(preview_legend);
+                }
+                let charts = egui::CollapsingHeader::new("Charts").id_salt("charts").show(ui, |ui| self.charts(ui));
+                // Closed charts sample nothing, and a gap in the frames would only skew the chart once reopened.
+                if charts.body_returned.is_none() {
+                    self.fps_history.clear();
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Pattern file");
+                    ui.text_edit_singleline(&mut self.pattern_path);
+                    if ui.button("Save").clicked() {
+                        self.save_to_file();
+                    }
+                    if ui.button("Load").clicked() {
+                        self.load_from_file(ui.ctx());
+                    }
+                    egui::ComboBox::from_id_salt("pattern_library")
+                        .selected_text("Library")
+                        .show_ui(ui, |ui| {
+                            for pattern in &patterns::ALL {
+                                if ui.selectable_label(false, pattern.name).clicked() {
+                                    self.place_library_pattern(ui.ctx(), pattern);
+                                }
+                            }
+                        });
+                    ui.label(&self.status);
+                });
+                ui.horizontal(|ui| {
+                    self.theme_controls(ui);
+                    ui.label("Cell shape");
+                    egui::ComboBox::from_id_salt("cell_shape")
+                        .selected_text(self.cell_shape.name())
+                        .show_ui(ui, |ui| {
+                            for shape in CellShape::ALL {
+                                ui.selectable_value(&mut self.cell_shape, shape, shape.name());
+                            }
+                        });
+                    ui.add(egui::Slider::new(&mut self.brush_radius, 0..=MAX_BRUSH_RADIUS).text("Brush radius"))
+                        .on_hover_text("Shift-drag over the board to randomize the cells within this many cells of the cursor");
+                    ui.add(egui::Slider::new(&mut self.brush_density, 0.0..=1.0).text("Brush density"))
+                        .on_hover_text("Share of the randomized cells that come out alive");
                 });
 
                 self.create_grid(ui);
             });
         });
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, THEME_STORAGE_KEY, &self.theme);
+        let state = self.state.lock().unwrap();
+        let session = SessionRef {
+            grid: &state.grid,
+            running: state.running,
+            step_interval_ms: state.step_interval.as_millis() as u64,
+            zoom: self.zoom,
+            pattern_path: &self.pattern_path,
+            show_neighbor_counts: self.show_neighbor_counts,
+            cell_shape: self.cell_shape,
+            preview: self.preview,
+        };
+        eframe::set_value(storage, SESSION_STORAGE_KEY, &session);
+    }
 }
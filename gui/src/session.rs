@@ -0,0 +1,34 @@
+//! The board and view settings kept in eframe's persisted storage, so closing and reopening the app resumes where it
+//! left off. The theme is stored on its own, under its own key.
+use serde::{Deserialize, Serialize};
+use shared::grid::Grid;
+
+use crate::CellShape;
+
+/// Written on save while the simulation lock is held, so the grid doesn't have to be copied.
+#[derive(Serialize)]
+#[serde(rename = "Session")]
+pub struct SessionRef<'a> {
+    pub grid: &'a Grid,
+    pub running: bool,
+    pub step_interval_ms: u64,
+    pub zoom: f32,
+    pub pattern_path: &'a str,
+    pub show_neighbor_counts: bool,
+    pub cell_shape: CellShape,
+    pub preview: bool,
+}
+
+/// Read back at startup; a session that no longer parses is ignored and the app starts fresh.
+#[derive(Deserialize)]
+#[serde(rename = "Session")]
+pub struct Session {
+    pub grid: Grid,
+    pub running: bool,
+    pub step_interval_ms: u64,
+    pub zoom: f32,
+    pub pattern_path: String,
+    pub show_neighbor_counts: bool,
+    pub cell_shape: CellShape,
+    pub preview: bool,
+}
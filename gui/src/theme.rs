@@ -0,0 +1,74 @@
+//! Board colors, with a few presets and room for custom picks.
+use eframe::egui::{self, Color32};
+use serde::{Deserialize, Serialize};
+use shared::colors::Classic;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    pub alive: Color32,
+    pub dead: Color32,
+    /// Shows between the rounded cell corners.
+    pub background: Color32,
+    /// Light or dark look for the egui widgets around the board.
+    pub mode: egui::Theme,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        alive: Color32::WHITE,
+        dead: Color32::DARK_GRAY,
+        background: Color32::from_gray(27),
+        mode: egui::Theme::Dark,
+    };
+
+    pub const LIGHT: Theme = Theme {
+        alive: Color32::from_gray(30),
+        dead: Color32::from_gray(215),
+        background: Color32::from_gray(248),
+        mode: egui::Theme::Light,
+    };
+
+    /// Green phosphor on black, like the old terminals.
+    pub const CLASSIC: Theme = Theme {
+        alive: Color32::from_rgb(51, 255, 102),
+        dead: Color32::from_rgb(8, 28, 12),
+        background: Color32::BLACK,
+        mode: egui::Theme::Dark,
+    };
+
+    /// Presets offered in the theme picker, in display order.
+    pub const PRESETS: [(&'static str, Theme); 3] = [("Dark", Theme::DARK), ("Light", Theme::LIGHT), ("Classic", Theme::CLASSIC)];
+
+    /// The cell colors as a shared color scheme; dying stages keep the default blue.
+    pub fn color_scheme(&self) -> Classic {
+        Classic {
+            alive: to_rgb(self.alive),
+            dead: to_rgb(self.dead),
+            ..Classic::DEFAULT
+        }
+    }
+
+    /// Name of the preset this theme matches, or "Custom" once a color has been changed.
+    pub fn name(&self) -> &'static str {
+        Theme::PRESETS
+            .iter()
+            .find(|(_, preset)| preset == self)
+            .map_or("Custom", |(name, _)| name)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DARK
+    }
+}
+
+fn to_rgb(color: Color32) -> [f32; 3] {
+    [color.r(), color.g(), color.b()].map(|channel| f32::from(channel) / 255.0)
+}
+
+/// Convert a color scheme's RGB triple back into an opaque egui color.
+pub fn to_color32(rgb: [f32; 3]) -> Color32 {
+    let [r, g, b] = rgb.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+    Color32::from_rgb(r, g, b)
+}
@@ -0,0 +1,157 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use clap::{Parser, ValueEnum};
+use image::{Rgb, RgbImage};
+use shared::grid::{CellState, Grid, Rule};
+
+const ALIVE_COLOR: Rgb<u8> = Rgb([242, 242, 242]);
+const DEAD_COLOR: Rgb<u8> = Rgb([46, 46, 56]);
+/// Color of the first dying stage of a Generations rule; later stages fade towards `DEAD_COLOR`.
+const DYING_COLOR: Rgb<u8> = Rgb([77, 128, 242]);
+/// Smallest board edge accepted; the neighbor counting assumes at least 3x3.
+const MIN_GRID_DIMENSION: usize = 3;
+
+/// Run a pattern for a number of generations without a window and write every generation to disk.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Pattern to start from: `.cells` files are read as plaintext, anything else as RLE
+    pattern: PathBuf,
+
+    /// Number of generations to advance
+    #[arg(long, default_value_t = 100)]
+    steps: usize,
+
+    /// Directory the generations are written to; created if missing
+    #[arg(long)]
+    output: PathBuf,
+
+    /// File format of each generation
+    #[arg(long, value_enum, default_value_t = Format::Png)]
+    format: Format,
+
+    /// Board width [default: the pattern's width]
+    #[arg(long, value_parser = parse_dimension)]
+    width: Option<usize>,
+
+    /// Board height [default: the pattern's height]
+    #[arg(long, value_parser = parse_dimension)]
+    height: Option<usize>,
+
+    /// Rule in B/S notation, e.g. B2/S/C3 [default: the pattern's rule, or B3/S23]
+    #[arg(long)]
+    rule: Option<Rule>,
+
+    /// Edge length of one cell in PNG output, in pixels
+    #[arg(long, default_value_t = 4)]
+    cell_size: u32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Png,
+    Rle,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Rle => "rle",
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if args.cell_size == 0 {
+        bail!("--cell-size must be at least 1");
+    }
+
+    let pattern = read_pattern(&args.pattern)?;
+    let mut grid = place_pattern(&pattern, args.width, args.height)?;
+    if let Some(rule) = args.rule {
+        grid.set_rule(rule);
+    }
+    fs::create_dir_all(&args.output).with_context(|| format!("create {}", args.output.display()))?;
+
+    // Zero-pad file names so they sort in generation order.
+    let digits = args.steps.to_string().len();
+    for step in 0..=args.steps {
+        if step > 0 {
+            grid.advance();
+        }
+        let path = args.output.join(format!("gen-{step:0digits$}.{}", args.format.extension()));
+        match args.format {
+            Format::Png => render_png(&grid, args.cell_size).save(&path),
+            Format::Rle => fs::write(&path, grid.to_rle()).map_err(Into::into),
+        }
+        .with_context(|| format!("write {}", path.display()))?;
+    }
+
+    println!("wrote {} generations to {}", args.steps + 1, args.output.display());
+    Ok(())
+}
+
+/// Read a pattern file, choosing the parser by extension: `.cells` is plaintext, anything else RLE.
+fn read_pattern(path: &Path) -> anyhow::Result<Grid> {
+    let text = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let is_plaintext = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("cells"));
+    let pattern = if is_plaintext {
+        Grid::from_plaintext(&text)?
+    } else {
+        Grid::from_rle(&text)?
+    };
+    Ok(pattern)
+}
+
+/// Center `pattern` on a board of the requested size, which defaults to the pattern's own size, grown to at least
+/// 3x3. The board takes over the pattern's rule.
+fn place_pattern(pattern: &Grid, width: Option<usize>, height: Option<usize>) -> anyhow::Result<Grid> {
+    let pattern_width = pattern.width();
+    let pattern_height = pattern.height();
+    let width = width.unwrap_or(pattern_width.max(MIN_GRID_DIMENSION));
+    let height = height.unwrap_or(pattern_height.max(MIN_GRID_DIMENSION));
+    if pattern_width > width || pattern_height > height {
+        bail!("the {pattern_width}x{pattern_height} pattern does not fit on a {width}x{height} board");
+    }
+
+    let mut grid = Grid::new(width, height);
+    grid.set_rule(pattern.rule());
+    grid.stamp(pattern, (height - pattern_height) / 2, (width - pattern_width) / 2);
+    Ok(grid)
+}
+
+/// Parse a board edge length for `--width`/`--height`.
+fn parse_dimension(value: &str) -> Result<usize, String> {
+    let dimension: usize = value.parse().map_err(|err| format!("`{value}` is not a whole number: {err}"))?;
+    if dimension < MIN_GRID_DIMENSION {
+        return Err(format!("must be at least {MIN_GRID_DIMENSION}, got {dimension}"));
+    }
+    Ok(dimension)
+}
+
+fn render_png(grid: &Grid, cell_size: u32) -> RgbImage {
+    let width = grid.width() as u32;
+    let height = grid.height() as u32;
+    let states = grid.rule().states();
+    RgbImage::from_fn(width * cell_size, height * cell_size, |x, y| {
+        match grid.cells[(y / cell_size) as usize][(x / cell_size) as usize] {
+            CellState::Alive => ALIVE_COLOR,
+            CellState::Dying(stage) => dying_color(stage, states),
+            CellState::Dead => DEAD_COLOR,
+        }
+    })
+}
+
+/// Fade through the dying stages, stopping short of `DEAD_COLOR` so the last stage stays visible.
+fn dying_color(stage: u8, states: u8) -> Rgb<u8> {
+    let last_stage = states.saturating_sub(2).max(1);
+    let t = 0.75 * f32::from(stage.saturating_sub(1)) / f32::from(last_stage);
+    Rgb(std::array::from_fn(|channel| {
+        let (from, to) = (f32::from(DYING_COLOR[channel]), f32::from(DEAD_COLOR[channel]));
+        (from + (to - from) * t).round() as u8
+    }))
+}
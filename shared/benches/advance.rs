@@ -0,0 +1,59 @@
+//! `advance` on random soups of a few sizes and densities. Run with `cargo bench -p shared`, adding
+//! `--features parallel` or `--features bitpacked` to measure those paths.
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+#[cfg(feature = "bitpacked")]
+use shared::grid::BitGrid;
+use shared::grid::Grid;
+
+/// Edge lengths of the square boards.
+const SIZES: [usize; 3] = [128, 512, 1024];
+/// Share of cells alive in the starting soup.
+const DENSITIES: [f64; 2] = [0.2, 0.5];
+const SEED: u64 = 12345;
+
+/// The same soup for every run, so numbers stay comparable across commits.
+fn soup(size: usize, density: f64) -> Grid {
+    let mut grid = Grid::new(size, size);
+    grid.randomize_with_density_rng(density, &mut StdRng::seed_from_u64(SEED));
+    grid
+}
+
+/// Every iteration steps a fresh soup, so the density being measured is the one the benchmark is named after.
+fn advance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("advance");
+    for size in SIZES {
+        group.throughput(Throughput::Elements((size * size) as u64));
+        for density in DENSITIES {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{size}x{size}"), density),
+                &(size, density),
+                |b, &(size, density)| b.iter_batched_ref(|| soup(size, density), Grid::advance, BatchSize::LargeInput),
+            );
+        }
+    }
+    group.finish();
+}
+
+#[cfg(feature = "bitpacked")]
+fn advance_bitpacked(c: &mut Criterion) {
+    let mut group = c.benchmark_group("advance_bitpacked");
+    for size in SIZES {
+        group.throughput(Throughput::Elements((size * size) as u64));
+        for density in DENSITIES {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{size}x{size}"), density),
+                &(size, density),
+                |b, &(size, density)| b.iter_batched_ref(|| BitGrid::from_grid(&soup(size, density)), BitGrid::advance, BatchSize::LargeInput),
+            );
+        }
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "bitpacked"))]
+criterion_group!(benches, advance);
+#[cfg(feature = "bitpacked")]
+criterion_group!(benches, advance, advance_bitpacked);
+criterion_main!(benches);
@@ -0,0 +1,161 @@
+//! Cell color schemes shared by the front-ends, so a look is defined once and every renderer draws it the same way.
+//! Colors are RGB triples with channels in `0.0..=1.0`.
+use crate::grid::CellState::{self, Alive, Dead, Dying};
+
+/// Turns a cell into the color it is drawn in.
+pub trait ColorScheme {
+    /// Color of a cell in `state`. `age` is how many generations it has been alive, when the grid tracks ages, and
+    /// `states` is the rule's [`states`](crate::grid::Rule::states), which dying stages are spread across.
+    fn color(&self, state: CellState, age: Option<u32>, states: u8) -> [f32; 3];
+}
+
+/// Flat alive and dead colors, with dying stages fading from `dying` towards `dead`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Classic {
+    pub alive: [f32; 3],
+    pub dead: [f32; 3],
+    /// Color of the first dying stage of a Generations rule.
+    pub dying: [f32; 3],
+}
+
+impl Classic {
+    pub const DEFAULT: Classic = Classic {
+        alive: [0.95, 0.95, 0.95],
+        dead: [0.18, 0.18, 0.22],
+        dying: [0.3, 0.5, 0.95],
+    };
+}
+
+impl Default for Classic {
+    fn default() -> Self {
+        Classic::DEFAULT
+    }
+}
+
+impl ColorScheme for Classic {
+    fn color(&self, state: CellState, _age: Option<u32>, states: u8) -> [f32; 3] {
+        match state {
+            Alive => self.alive,
+            // Stop short of the dead color so the last stage stays visible.
+            Dying(stage) => {
+                let last_stage = states.saturating_sub(2).max(1);
+                mix(self.dying, self.dead, 0.75 * f32::from(stage.saturating_sub(1)) / f32::from(last_stage))
+            }
+            Dead => self.dead,
+        }
+    }
+}
+
+/// Alive cells fade from warm and bright when newborn to cool blue once they reach `max_age`; everything else, and
+/// alive cells of grids without age tracking, look as in `base`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heatmap {
+    pub base: Classic,
+    pub newborn: [f32; 3],
+    pub ancient: [f32; 3],
+    pub max_age: u32,
+}
+
+impl Heatmap {
+    pub const DEFAULT: Heatmap = Heatmap {
+        base: Classic::DEFAULT,
+        newborn: [1.0, 0.92, 0.55],
+        ancient: [0.2, 0.35, 0.85],
+        max_age: 100,
+    };
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Heatmap::DEFAULT
+    }
+}
+
+impl ColorScheme for Heatmap {
+    fn color(&self, state: CellState, age: Option<u32>, states: u8) -> [f32; 3] {
+        match (state, age) {
+            (Alive, Some(age)) => {
+                // The square root spends more of the gradient on young cells, where most of the activity is.
+                let t = (age.min(self.max_age) as f32 / self.max_age.max(1) as f32).sqrt();
+                mix(self.newborn, self.ancient, t)
+            }
+            _ => self.base.color(state, age, states),
+        }
+    }
+}
+
+/// Two colors only: dying cells are drawn as dead, since they neither count as neighbors nor can be reborn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Monochrome {
+    pub alive: [f32; 3],
+    pub dead: [f32; 3],
+}
+
+impl Monochrome {
+    pub const DEFAULT: Monochrome = Monochrome {
+        alive: [1.0, 1.0, 1.0],
+        dead: [0.0, 0.0, 0.0],
+    };
+}
+
+impl Default for Monochrome {
+    fn default() -> Self {
+        Monochrome::DEFAULT
+    }
+}
+
+impl ColorScheme for Monochrome {
+    fn color(&self, state: CellState, _age: Option<u32>, _states: u8) -> [f32; 3] {
+        if state == Alive {
+            self.alive
+        } else {
+            self.dead
+        }
+    }
+}
+
+/// Linear blend from `from` (`t = 0`) to `to` (`t = 1`).
+pub fn mix(from: [f32; 3], to: [f32; 3], t: f32) -> [f32; 3] {
+    std::array::from_fn(|channel| from[channel] + (to[channel] - from[channel]) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        assert!(
+            actual.iter().zip(expected).all(|(a, e)| (a - e).abs() < 1e-6),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn classic_fades_dying_stages_without_reaching_dead() {
+        let scheme = Classic::DEFAULT;
+        assert_eq!(scheme.color(Alive, None, 2), scheme.alive);
+        assert_eq!(scheme.color(Dead, None, 2), scheme.dead);
+        assert_eq!(scheme.color(Dying(1), None, 4), scheme.dying);
+        let last = scheme.color(Dying(2), None, 4);
+        assert_ne!(last, scheme.dead);
+        assert_eq!(last, mix(scheme.dying, scheme.dead, 0.375));
+    }
+
+    #[test]
+    fn heatmap_colors_alive_cells_by_age_only() {
+        let scheme = Heatmap::DEFAULT;
+        assert_eq!(scheme.color(Alive, Some(0), 2), scheme.newborn);
+        assert_close(scheme.color(Alive, Some(scheme.max_age), 2), scheme.ancient);
+        assert_close(scheme.color(Alive, Some(scheme.max_age * 10), 2), scheme.ancient);
+        assert_eq!(scheme.color(Alive, None, 2), scheme.base.alive);
+        assert_eq!(scheme.color(Dead, Some(0), 2), scheme.base.dead);
+    }
+
+    #[test]
+    fn monochrome_draws_dying_cells_as_dead() {
+        let scheme = Monochrome::DEFAULT;
+        assert_eq!(scheme.color(Alive, None, 3), scheme.alive);
+        assert_eq!(scheme.color(Dying(1), None, 3), scheme.dead);
+        assert_eq!(scheme.color(Dead, None, 3), scheme.dead);
+    }
+}
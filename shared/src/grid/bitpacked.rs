@@ -0,0 +1,237 @@
+//! Bit-packed grid storage, enabled by the `bitpacked` feature.
+//!
+//! [`BitGrid`] keeps one bit per cell in a single flat `Vec<u64>`. Every row starts on a word boundary so that
+//! neighbor counts for 64 cells at a time can be computed with shifts and a bit-sliced adder. It implements the
+//! classic B3/S23 rule on a torus with the Moore neighborhood; convert to and from [`Grid`] for everything else.
+//! Having only two states, it stores dying cells as dead.
+use super::CellState::{self, Alive, Dead, Dying};
+use super::Grid;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+    next_bits: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        BitGrid {
+            width,
+            height,
+            words_per_row,
+            bits: vec![0; words_per_row * height],
+            next_bits: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn from_grid(grid: &Grid) -> Self {
+        let height = grid.height();
+        let width = grid.width();
+        let mut bit_grid = BitGrid::new(width, height);
+        for (row_index, row) in grid.cells.iter().enumerate() {
+            for (col_index, cell) in row.iter().enumerate() {
+                bit_grid.set(row_index, col_index, *cell);
+            }
+        }
+        bit_grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> CellState {
+        let (word, mask) = self.locate(row, col);
+        if self.bits[word] & mask != 0 {
+            Alive
+        } else {
+            Dead
+        }
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, state: CellState) {
+        let (word, mask) = self.locate(row, col);
+        match state {
+            Alive => self.bits[word] |= mask,
+            Dead | Dying(_) => self.bits[word] &= !mask,
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.bits.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Unpack into the `Vec<Vec<CellState>>` layout used by [`Grid::cells`].
+    pub fn cells(&self) -> Vec<Vec<CellState>> {
+        (0..self.height)
+            .map(|row| (0..self.width).map(|col| self.get(row, col)).collect())
+            .collect()
+    }
+
+    pub fn to_grid(&self) -> Grid {
+        let mut grid = Grid::new(self.width, self.height);
+        grid.cells = self.cells();
+        grid.population = self.population();
+        grid
+    }
+
+    /// Advance by one generation. Returns `false` when nothing changed.
+    pub fn advance(&mut self) -> bool {
+        if self.width == 0 || self.height == 0 {
+            return false;
+        }
+
+        let words = self.words_per_row;
+        let last_word_mask = match self.width % WORD_BITS {
+            0 => u64::MAX,
+            used => (1 << used) - 1,
+        };
+        let mut west = vec![0; 3 * words];
+        let mut east = vec![0; 3 * words];
+        let mut next_bits = std::mem::take(&mut self.next_bits);
+        let mut changed = false;
+
+        for row in 0..self.height {
+            let up = if row == 0 { self.height - 1 } else { row - 1 };
+            let down = if row == self.height - 1 { 0 } else { row + 1 };
+            let rows = [self.row(up), self.row(row), self.row(down)];
+
+            for (index, source) in rows.iter().enumerate() {
+                self.shift_west(source, &mut west[index * words..(index + 1) * words]);
+                self.shift_east(source, &mut east[index * words..(index + 1) * words]);
+            }
+
+            for word in 0..words {
+                let neighbors = [
+                    west[word],
+                    rows[0][word],
+                    east[word],
+                    west[words + word],
+                    east[words + word],
+                    west[2 * words + word],
+                    rows[2][word],
+                    east[2 * words + word],
+                ];
+
+                // Bit-sliced counter: `ones` and `twos` hold the low bits of each cell's count, `fours` saturates.
+                let (mut ones, mut twos, mut fours) = (0u64, 0u64, 0u64);
+                for neighbor in neighbors {
+                    let carry = ones & neighbor;
+                    ones ^= neighbor;
+                    fours |= twos & carry;
+                    twos ^= carry;
+                }
+
+                let current = rows[1][word];
+                let mut next = !fours & twos & (ones | current);
+                if word == words - 1 {
+                    next &= last_word_mask;
+                }
+                changed |= next != current;
+                next_bits[row * words + word] = next;
+            }
+        }
+
+        self.next_bits = next_bits;
+        if changed {
+            std::mem::swap(&mut self.bits, &mut self.next_bits);
+        }
+        changed
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        &self.bits[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    fn locate(&self, row: usize, col: usize) -> (usize, u64) {
+        assert!(row < self.height && col < self.width, "cell ({row}, {col}) out of bounds");
+        (row * self.words_per_row + col / WORD_BITS, 1 << (col % WORD_BITS))
+    }
+
+    /// Bit `c` of `out` becomes bit `c - 1` of `row`, with column 0 wrapping around to the last column.
+    fn shift_west(&self, row: &[u64], out: &mut [u64]) {
+        let last = self.width - 1;
+        let mut carry = (row[last / WORD_BITS] >> (last % WORD_BITS)) & 1;
+        for (source, target) in row.iter().zip(out.iter_mut()) {
+            *target = (source << 1) | carry;
+            carry = source >> (WORD_BITS - 1);
+        }
+        // The bit shifted past the last column is padding and must stay clear.
+        if !self.width.is_multiple_of(WORD_BITS) {
+            out[last / WORD_BITS] &= !(1 << (self.width % WORD_BITS));
+        }
+    }
+
+    /// Bit `c` of `out` becomes bit `c + 1` of `row`, with the last column wrapping around to column 0.
+    fn shift_east(&self, row: &[u64], out: &mut [u64]) {
+        for (index, target) in out.iter_mut().enumerate() {
+            let next = row.get(index + 1).copied().unwrap_or(0);
+            *target = (row[index] >> 1) | (next << (WORD_BITS - 1));
+        }
+        let last = self.width - 1;
+        out[last / WORD_BITS] |= (row[0] & 1) << (last % WORD_BITS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn assert_matches_grid(width: usize, height: usize, seed: u64) {
+        let mut grid = Grid::new(width, height);
+        let mut rng = StdRng::seed_from_u64(seed);
+        grid.randomize_with_density_rng(0.5, &mut rng);
+        let mut bit_grid = BitGrid::from_grid(&grid);
+
+        for _ in 0..30 {
+            assert_eq!(bit_grid.advance(), grid.advance());
+            assert_eq!(bit_grid.cells(), grid.cells, "{width}x{height} diverged");
+            assert_eq!(bit_grid.population(), grid.population());
+        }
+    }
+
+    #[test]
+    fn bit_grid_matches_grid_across_word_boundaries() {
+        for (width, height) in [(3, 3), (63, 5), (64, 7), (65, 9), (130, 40), (1, 4), (70, 1)] {
+            assert_matches_grid(width, height, width as u64 * 31 + height as u64);
+        }
+    }
+
+    #[test]
+    fn bit_grid_round_trips_through_grid() {
+        let mut grid = Grid::new(70, 3);
+        grid.cells[0][0] = Alive;
+        grid.cells[1][69] = Alive;
+        grid.cells[2][64] = Alive;
+        let restored = BitGrid::from_grid(&grid).to_grid();
+        assert_eq!(restored.cells, grid.cells);
+        assert_eq!(restored.population(), 3);
+    }
+
+    #[test]
+    fn bit_grid_wraps_glider_across_edges() {
+        let glider = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        let mut grid = Grid::new(66, 6);
+        for (row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            grid.cells[row + 3][col + 63] = glider.cells[row][col];
+        }
+        grid.population = 5;
+        let mut bit_grid = BitGrid::from_grid(&grid);
+        for _ in 0..24 {
+            grid.advance();
+            bit_grid.advance();
+        }
+        assert_eq!(bit_grid.cells(), grid.cells);
+        assert_eq!(bit_grid.population(), 5);
+    }
+}
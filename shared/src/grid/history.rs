@@ -0,0 +1,146 @@
+//! Compact snapshots of past generations for [`Grid::step_back`](super::Grid::step_back).
+//!
+//! A snapshot stores the cells in row-major order as runs of equal states: one state byte (0 dead, 1 alive,
+//! `1 + n` for dying stage `n`) followed by the run length as a LEB128 varint. Most boards are largely dead, so a
+//! generation usually takes a small fraction of the two bytes per cell a `Vec<Vec<CellState>>` needs.
+use super::CellState::{self, Alive, Dead, Dying};
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Snapshot {
+    width: usize,
+    height: usize,
+    bytes: Vec<u8>,
+}
+
+impl Snapshot {
+    /// Replace the contents with an encoding of `cells`, reusing the existing buffer.
+    pub(super) fn encode_from(&mut self, cells: &[Vec<CellState>]) {
+        self.width = cells.first().map_or(0, Vec::len);
+        self.height = cells.len();
+        self.bytes.clear();
+
+        let mut cells = cells.iter().flatten();
+        let Some(&first) = cells.next() else {
+            return;
+        };
+        let (mut state, mut run) = (first, 1);
+        for &cell in cells {
+            if cell == state {
+                run += 1;
+            } else {
+                self.push_run(state, run);
+                (state, run) = (cell, 1);
+            }
+        }
+        self.push_run(state, run);
+    }
+
+    /// Write the encoded generation back into `cells`, resizing it to the snapshot's dimensions.
+    pub(super) fn decode_into(&self, cells: &mut Vec<Vec<CellState>>) {
+        cells.resize_with(self.height, Vec::new);
+        for row in cells.iter_mut() {
+            row.clear();
+        }
+
+        let mut bytes = self.bytes.iter().copied();
+        let mut rows = cells.iter_mut();
+        let mut row = rows.next();
+        while let Some(state_byte) = bytes.next() {
+            let state = match state_byte {
+                0 => Dead,
+                1 => Alive,
+                stage => Dying(stage - 1),
+            };
+            let mut run = read_varint(&mut bytes);
+            while run > 0 {
+                let Some(target) = row.as_mut() else {
+                    return;
+                };
+                let count = run.min(self.width - target.len());
+                target.extend(std::iter::repeat_n(state, count));
+                run -= count;
+                if target.len() == self.width {
+                    row = rows.next();
+                }
+            }
+        }
+    }
+
+    /// Width and height of the encoded generation.
+    pub(super) fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Size of the encoded cells in bytes.
+    pub(super) fn encoded_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn push_run(&mut self, state: CellState, run: usize) {
+        self.bytes.push(match state {
+            Dead => 0,
+            Alive => 1,
+            // Rules have at most 255 states, so dying stages never go past 253.
+            Dying(stage) => stage.saturating_add(1),
+        });
+        let mut run = run;
+        while run >= 0x80 {
+            self.bytes.push((run as u8 & 0x7f) | 0x80);
+            run >>= 7;
+        }
+        self.bytes.push(run as u8);
+    }
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> usize {
+    let mut value = 0;
+    let mut shift = 0;
+    for byte in bytes {
+        value |= usize::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn round_trip(cells: &[Vec<CellState>]) -> Vec<Vec<CellState>> {
+        let mut snapshot = Snapshot::default();
+        snapshot.encode_from(cells);
+        // Start from a differently shaped buffer to check that decoding resizes it.
+        let mut decoded = vec![vec![Alive; 2]; 7];
+        snapshot.decode_into(&mut decoded);
+        decoded
+    }
+
+    #[test]
+    fn decoded_snapshots_match_the_original_cells() {
+        let mut random = Grid::new(150, 40);
+        random.randomize_with_density_rng(0.3, &mut StdRng::seed_from_u64(5));
+        let patterns = [
+            Grid::new(20, 10).cells,
+            vec![vec![Alive; 300]; 3],
+            vec![vec![Dead, Alive, Dying(1)], vec![Dying(253), Dead, Dead]],
+            random.cells,
+            Vec::new(),
+        ];
+        for cells in patterns {
+            assert_eq!(round_trip(&cells), cells);
+        }
+    }
+
+    #[test]
+    fn runs_span_rows_and_use_varint_lengths() {
+        let mut snapshot = Snapshot::default();
+        snapshot.encode_from(&vec![vec![Dead; 100]; 100]);
+        // One run of 10,000 dead cells: the state byte plus a two-byte varint.
+        assert_eq!(snapshot.encoded_len(), 3);
+    }
+}
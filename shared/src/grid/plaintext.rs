@@ -0,0 +1,93 @@
+//! Plaintext (`.cells`) pattern support.
+//!
+//! Each line is one row where `.` is a dead cell and `O` an alive cell. Lines starting with `!` are comments.
+use super::CellState::{Alive, Dead};
+use super::Grid;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The text contains no rows once comments are removed.
+    Empty,
+    /// A row contains something other than `.` or `O`.
+    UnexpectedCharacter { line: usize, character: char },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "pattern contains no rows"),
+            ParseError::UnexpectedCharacter { line, character } => write!(f, "unexpected character `{character}` on line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Grid {
+    /// Parse a plaintext pattern. The longest row sets the grid width and shorter rows are padded with dead cells.
+    pub fn from_plaintext(text: &str) -> Result<Grid, ParseError> {
+        let mut rows = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.starts_with('!') {
+                continue;
+            }
+            let row = line
+                .trim_end()
+                .chars()
+                .map(|character| match character {
+                    '.' => Ok(Dead),
+                    'O' => Ok(Alive),
+                    _ => Err(ParseError::UnexpectedCharacter { line: index + 1, character }),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if width == 0 {
+            return Err(ParseError::Empty);
+        }
+
+        let mut grid = Grid::new(width, rows.len());
+        for (cells, row) in grid.cells.iter_mut().zip(rows) {
+            cells[..row.len()].copy_from_slice(&row);
+        }
+        grid.population = grid.cells.iter().flatten().filter(|cell| **cell == Alive).count();
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_plaintext_loads_glider_with_comments() {
+        let text = "!Name: Glider\n!Author: Richard K. Guy\n.O\n..O\nOOO\n";
+        let grid = Grid::from_plaintext(text).unwrap();
+        let expected = vec![vec![Dead, Alive, Dead], vec![Dead, Dead, Alive], vec![Alive, Alive, Alive]];
+        assert_eq!(grid.cells, expected);
+        assert_eq!(grid.population(), 5);
+    }
+
+    #[test]
+    fn from_plaintext_pads_short_rows_and_keeps_blank_rows() {
+        let grid = Grid::from_plaintext("O\n\n..O").unwrap();
+        let expected = vec![vec![Alive, Dead, Dead], vec![Dead, Dead, Dead], vec![Dead, Dead, Alive]];
+        assert_eq!(grid.cells, expected);
+    }
+
+    #[test]
+    fn from_plaintext_rejects_empty_pattern() {
+        assert_eq!(Grid::from_plaintext("!just a comment\n").err(), Some(ParseError::Empty));
+    }
+
+    #[test]
+    fn from_plaintext_rejects_unknown_characters() {
+        assert_eq!(
+            Grid::from_plaintext(".O\nOX").err(),
+            Some(ParseError::UnexpectedCharacter { line: 2, character: 'X' })
+        );
+    }
+}
@@ -0,0 +1,540 @@
+//! Run Length Encoded (`.rle`) pattern support.
+//!
+//! A file consists of optional `#` comment lines, a header such as `x = 3, y = 3, rule = B3/S23`
+//! and a body where `b` is a dead cell, `o` an alive cell, `$` ends a row and `!` ends the pattern.
+//! Any of those tags may be prefixed by a run count. Of the comment lines, `#N` names the pattern, `#O` credits its
+//! author and `#C` holds free-form comments.
+//!
+//! Multi-state rules use Golly's extended tags instead: `.` for dead, `A` for alive and `B`, `C`, ... for the
+//! dying stages, with a `p`-`y` prefix selecting further blocks of 24 states.
+use super::CellState::{Alive, Dead, Dying};
+use super::{CellState, Grid, Rule};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+
+/// Longest body line emitted by `to_rle`, as recommended by the format description.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Most cells a header may declare, 8192 x 8192; the grid is allocated up front, so a bogus header must not abort the
+/// process on allocation.
+const MAX_CELLS: usize = 1 << 26;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RleError {
+    /// No `x = .., y = ..` line was found before the pattern body.
+    MissingHeader,
+    /// The header line could not be understood, including an unparsable `rule`.
+    InvalidHeader(String),
+    /// The body contains a character that is not a run count, a cell tag valid for the rule, or `$`/`!`.
+    UnexpectedCharacter { line: usize, character: char },
+    /// A run count does not fit into `usize`.
+    InvalidRunCount { line: usize },
+    /// The header declares more than `MAX_CELLS` cells.
+    TooLarge { width: usize, height: usize },
+    /// The body places alive cells outside of the dimensions declared in the header.
+    OutOfBounds { line: usize, row: usize, col: usize },
+    /// Reading the source failed, or it isn't UTF-8.
+    Io(String),
+}
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RleError::MissingHeader => write!(f, "missing `x = .., y = ..` header line"),
+            RleError::InvalidHeader(header) => write!(f, "invalid header line: `{header}`"),
+            RleError::UnexpectedCharacter { line, character } => write!(f, "unexpected character `{character}` on line {line}"),
+            RleError::InvalidRunCount { line } => write!(f, "run count too large on line {line}"),
+            RleError::TooLarge { width, height } => write!(f, "pattern size {width}x{height} is too large"),
+            RleError::OutOfBounds { line, row, col } => {
+                write!(f, "cell at row {row}, column {col} on line {line} lies outside the declared pattern size")
+            }
+            RleError::Io(message) => write!(f, "failed to read pattern: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+/// Provenance kept in the `#` lines and the header of an RLE file, read by [`Grid::from_rle_with_meta`] and
+/// written back by [`Grid::to_rle_with_meta`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PatternMeta {
+    /// `#N` line.
+    pub name: Option<String>,
+    /// `#O` line.
+    pub author: Option<String>,
+    /// `#C` lines, in order.
+    pub comments: Vec<String>,
+    /// Rule from the header, which the parsed grid uses too. `to_rle_with_meta` writes the grid's own rule instead.
+    pub rule: Rule,
+}
+
+impl Grid {
+    /// Parse an RLE pattern into a grid sized to the dimensions declared in its header.
+    pub fn from_rle(text: &str) -> Result<Grid, RleError> {
+        Grid::from_rle_reader(text.as_bytes())
+    }
+
+    /// Like [`from_rle`](Self::from_rle), reading the pattern a line at a time so large files never have to be held
+    /// in memory next to the grid.
+    pub fn from_rle_reader(reader: impl Read) -> Result<Grid, RleError> {
+        read_rle(reader).map(|(grid, _)| grid)
+    }
+
+    /// Like [`from_rle`](Self::from_rle), also returning the pattern's name, author, comments and rule.
+    pub fn from_rle_with_meta(text: &str) -> Result<(Grid, PatternMeta), RleError> {
+        read_rle(text.as_bytes())
+    }
+
+    /// Encode the grid as RLE. Trailing dead cells of each row are dropped and empty rows are folded into `$` runs.
+    /// Multi-state rules are written with Golly's extended tags.
+    pub fn to_rle(&self) -> String {
+        self.to_rle_with_meta(&PatternMeta::default())
+    }
+
+    /// Like [`to_rle`](Self::to_rle), starting with `#N`, `#O` and `#C` lines for the name, author and comments in
+    /// `meta`.
+    pub fn to_rle_with_meta(&self, meta: &PatternMeta) -> String {
+        let mut header = String::new();
+        for (tag, text) in [("N", &meta.name), ("O", &meta.author)] {
+            if let Some(text) = text {
+                header.push_str(&format!("#{tag} {text}\n"));
+            }
+        }
+        for comment in &meta.comments {
+            header.push_str(&format!("#C {comment}\n"));
+        }
+        let height = self.height();
+        let width = self.width();
+        header.push_str(&format!("x = {width}, y = {height}, rule = {}\n", self.rule));
+        let mut writer = RleWriter::new(header);
+        let multistate = self.rule.states() > 2;
+        let mut last_row = None;
+
+        for (row_index, row) in self.cells.iter().enumerate() {
+            let Some(end) = row.iter().rposition(|cell| *cell != Dead).map(|col| col + 1) else {
+                continue;
+            };
+
+            let row_breaks = match last_row {
+                Some(last) => row_index - last,
+                None => row_index,
+            };
+            writer.push_run(row_breaks, "$");
+            last_row = Some(row_index);
+
+            let mut cells = row[..end].iter().peekable();
+            while let Some(&state) = cells.next() {
+                let mut count = 1;
+                while cells.next_if(|cell| **cell == state).is_some() {
+                    count += 1;
+                }
+                writer.push_run(count, &tag(state, multistate));
+            }
+        }
+
+        writer.finish()
+    }
+}
+
+/// Parse an RLE pattern along with the metadata in its comment lines and header.
+fn read_rle(reader: impl Read) -> Result<(Grid, PatternMeta), RleError> {
+    let mut meta = PatternMeta::default();
+    let mut lines = BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .map(|(index, line)| line.map(|line| (index + 1, line)).map_err(|err| RleError::Io(err.to_string())));
+
+    let (width, height, rule) = loop {
+        let Some((_, line)) = lines.next().transpose()? else {
+            return Err(RleError::MissingHeader);
+        };
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            // Other `#` lines, such as `#P` positions, don't survive a round trip.
+            let (tag, text) = comment.split_at(comment.chars().next().map_or(0, char::len_utf8));
+            let text = text.trim().to_string();
+            match tag {
+                "N" => meta.name = Some(text),
+                "O" => meta.author = Some(text),
+                "C" | "c" => meta.comments.push(text),
+                _ => {}
+            }
+        } else if !line.is_empty() {
+            break parse_header(line)?;
+        }
+    };
+
+    if width.checked_mul(height).is_none_or(|cells| cells > MAX_CELLS) {
+        return Err(RleError::TooLarge { width, height });
+    }
+    let mut grid = Grid::new(width, height);
+    grid.set_rule(rule);
+    meta.rule = rule;
+    let mut row: usize = 0;
+    let mut col: usize = 0;
+    let mut run: Option<usize> = None;
+    // Block selected by a `p`-`y` prefix of a multi-state tag.
+    let mut prefix: Option<u8> = None;
+
+    'body: for line in lines {
+        let (line_number, line) = line?;
+        for character in line.trim().chars() {
+            let unexpected = RleError::UnexpectedCharacter {
+                line: line_number,
+                character,
+            };
+            if prefix.is_some() && !character.is_ascii_uppercase() {
+                return Err(unexpected);
+            }
+            match character {
+                '0'..='9' => {
+                    let digit = character as usize - '0' as usize;
+                    let count = run
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit))
+                        .ok_or(RleError::InvalidRunCount { line: line_number })?;
+                    run = Some(count);
+                }
+                'b' | '.' => {
+                    col = col
+                        .checked_add(run.take().unwrap_or(1))
+                        .ok_or(RleError::InvalidRunCount { line: line_number })?;
+                }
+                'p'..='y' => prefix = Some(character as u8 - b'p' + 1),
+                'o' | 'A'..='X' => {
+                    let state = match character {
+                        'o' => Alive,
+                        letter => state_from_number(usize::from(prefix.take().unwrap_or(0)) * 24 + usize::from(letter as u8 - b'A') + 1),
+                    };
+                    if !grid.rule.allows(state) {
+                        return Err(unexpected);
+                    }
+                    for _ in 0..run.take().unwrap_or(1) {
+                        if row >= height || col >= width {
+                            return Err(RleError::OutOfBounds { line: line_number, row, col });
+                        }
+                        grid.cells[row][col] = state;
+                        if state == Alive {
+                            grid.population += 1;
+                        }
+                        col += 1;
+                    }
+                }
+                '$' => {
+                    row = row
+                        .checked_add(run.take().unwrap_or(1))
+                        .ok_or(RleError::InvalidRunCount { line: line_number })?;
+                    col = 0;
+                }
+                '!' => break 'body,
+                c if c.is_whitespace() => {}
+                _ => return Err(unexpected),
+            }
+        }
+    }
+
+    Ok((grid, meta))
+}
+
+fn tag(state: CellState, multistate: bool) -> String {
+    match (state, multistate) {
+        (Dead, false) => "b".to_string(),
+        (Alive, false) => "o".to_string(),
+        (Dead, true) => ".".to_string(),
+        (state, _) => {
+            // States 1-24 are `A`-`X`; higher ones prepend `p`, `q`, ... for each further block of 24.
+            let number = state_number(state) - 1;
+            let letter = char::from(b'A' + (number % 24) as u8);
+            match number / 24 {
+                0 => letter.to_string(),
+                block => format!("{}{letter}", char::from(b'p' + block as u8 - 1)),
+            }
+        }
+    }
+}
+
+/// Golly's state numbering: 0 is dead, 1 alive and 2 onwards the dying stages.
+fn state_number(state: CellState) -> usize {
+    match state {
+        Dead => 0,
+        Alive => 1,
+        Dying(stage) => usize::from(stage) + 1,
+    }
+}
+
+fn state_from_number(number: usize) -> CellState {
+    match number {
+        0 => Dead,
+        1 => Alive,
+        // Anything past the last `u8` stage is rejected by `Rule::allows` like any other unknown stage.
+        n => Dying(u8::try_from(n - 1).unwrap_or(u8::MAX)),
+    }
+}
+
+/// Accumulates body runs while keeping lines below `MAX_LINE_LENGTH`.
+struct RleWriter {
+    output: String,
+    line_length: usize,
+}
+
+impl RleWriter {
+    fn new(header: String) -> Self {
+        RleWriter {
+            output: header,
+            line_length: 0,
+        }
+    }
+
+    fn push_run(&mut self, count: usize, tag: &str) {
+        let token = match count {
+            0 => return,
+            1 => tag.to_string(),
+            _ => format!("{count}{tag}"),
+        };
+        self.push_token(&token);
+    }
+
+    fn push_token(&mut self, token: &str) {
+        if self.line_length + token.len() > MAX_LINE_LENGTH {
+            self.output.push('\n');
+            self.line_length = 0;
+        }
+        self.output.push_str(token);
+        self.line_length += token.len();
+    }
+
+    fn finish(mut self) -> String {
+        self.push_token("!");
+        self.output.push('\n');
+        self.output
+    }
+}
+
+/// Parse `x = <width>, y = <height>[, rule = <rule>]`; the rule defaults to Conway's Life.
+fn parse_header(line: &str) -> Result<(usize, usize, Rule), RleError> {
+    let invalid = || RleError::InvalidHeader(line.to_string());
+    let mut width = None;
+    let mut height = None;
+    let mut rule = Rule::CONWAY;
+
+    for entry in line.split(',') {
+        let (key, value) = entry.split_once('=').ok_or_else(invalid)?;
+        match key.trim() {
+            "x" => width = Some(value.trim().parse::<usize>().map_err(|_| invalid())?),
+            "y" => height = Some(value.trim().parse::<usize>().map_err(|_| invalid())?),
+            "rule" => rule = value.trim().parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height, rule)),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_rle_loads_glider() {
+        let grid = Grid::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        let expected = vec![vec![Dead, Alive, Dead], vec![Dead, Dead, Alive], vec![Alive, Alive, Alive]];
+        assert_eq!(grid.cells, expected);
+        assert_eq!(grid.population(), 5);
+    }
+
+    #[test]
+    fn from_rle_loads_blinker() {
+        let grid = Grid::from_rle("x = 3, y = 1\n3o!").unwrap();
+        assert_eq!(grid.cells, vec![vec![Alive, Alive, Alive]]);
+    }
+
+    #[test]
+    fn from_rle_handles_multi_row_runs_and_line_breaks() {
+        let grid = Grid::from_rle("x = 2, y = 4\no\n2$\nbo!").unwrap();
+        let expected = vec![vec![Alive, Dead], vec![Dead, Dead], vec![Dead, Alive], vec![Dead, Dead]];
+        assert_eq!(grid.cells, expected);
+    }
+
+    #[test]
+    fn from_rle_reader_matches_from_rle() {
+        let text = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$\n3o!\n";
+        let grid = Grid::from_rle_reader(std::io::Cursor::new(text)).unwrap();
+        assert_eq!(grid.cells, Grid::from_rle(text).unwrap().cells);
+        assert_eq!(grid.population(), 5);
+    }
+
+    #[test]
+    fn from_rle_reader_reports_invalid_utf8() {
+        let bytes: &[u8] = b"x = 3, y = 1\n\xff3o!";
+        assert!(matches!(Grid::from_rle_reader(bytes), Err(RleError::Io(_))));
+    }
+
+    fn pulsar() -> Grid {
+        let mut grid = Grid::new(17, 17);
+        for &line in &[0, 5, 7, 12] {
+            for &span in &[2, 3, 4, 8, 9, 10] {
+                grid.cells[line + 2][span + 2] = Alive;
+                grid.cells[span + 2][line + 2] = Alive;
+            }
+        }
+        grid.population = 48;
+        grid
+    }
+
+    #[test]
+    fn to_rle_encodes_glider() {
+        let grid = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        assert_eq!(grid.to_rle(), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    }
+
+    #[test]
+    fn to_rle_collapses_empty_rows_and_trims_trailing_dead_cells() {
+        let mut grid = Grid::new(5, 5);
+        grid.cells[1][0] = Alive;
+        grid.cells[4][2] = Alive;
+        assert_eq!(grid.to_rle(), "x = 5, y = 5, rule = B3/S23\n$o3$2bo!\n");
+    }
+
+    #[test]
+    fn to_rle_wraps_long_lines() {
+        let mut grid = Grid::new(200, 1);
+        for col in (0..200).step_by(2) {
+            grid.cells[0][col] = Alive;
+        }
+        let rle = grid.to_rle();
+        assert!(rle.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(Grid::from_rle(&rle).unwrap().cells, grid.cells);
+    }
+
+    #[test]
+    fn rle_round_trip_preserves_pulsar() {
+        let grid = pulsar();
+        let restored = Grid::from_rle(&grid.to_rle()).unwrap();
+        assert_eq!(restored.cells, grid.cells);
+        assert_eq!(restored.population(), 48);
+    }
+
+    #[test]
+    fn to_rle_of_empty_grid_is_just_terminator() {
+        assert_eq!(Grid::new(4, 3).to_rle(), "x = 4, y = 3, rule = B3/S23\n!\n");
+    }
+
+    #[test]
+    fn rle_round_trip_preserves_metadata() {
+        let meta = PatternMeta {
+            name: Some("Glider".to_string()),
+            author: Some("Richard K. Guy".to_string()),
+            comments: vec!["The smallest spaceship.".to_string(), "Found in 1969.".to_string()],
+            rule: Rule::BRIANS_BRAIN,
+        };
+        let mut grid = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        grid.set_rule(Rule::BRIANS_BRAIN);
+
+        let rle = grid.to_rle_with_meta(&meta);
+        assert!(rle.starts_with("#N Glider\n#O Richard K. Guy\n#C The smallest spaceship.\n#C Found in 1969.\nx = 3"));
+        let (restored, restored_meta) = Grid::from_rle_with_meta(&rle).unwrap();
+        assert_eq!(restored_meta, meta);
+        assert_eq!(restored.rule(), Rule::BRIANS_BRAIN);
+        assert_eq!(restored.cells, grid.cells);
+    }
+
+    #[test]
+    fn from_rle_with_meta_skips_other_comment_lines() {
+        let (_, meta) = Grid::from_rle_with_meta("#N Blinker\n#P 0 0\n#c lower-case comment\nx = 3, y = 1\n3o!").unwrap();
+        assert_eq!(meta.name.as_deref(), Some("Blinker"));
+        assert_eq!(meta.author, None);
+        assert_eq!(meta.comments, ["lower-case comment"]);
+        assert_eq!(meta.rule, Rule::CONWAY);
+    }
+
+    #[test]
+    fn from_rle_rejects_missing_header() {
+        assert_eq!(Grid::from_rle("#C only a comment\n").err(), Some(RleError::MissingHeader));
+    }
+
+    #[test]
+    fn from_rle_rejects_malformed_header() {
+        assert!(matches!(Grid::from_rle("x = three, y = 3\n3o!"), Err(RleError::InvalidHeader(_))));
+        assert!(matches!(Grid::from_rle("x = 3\n3o!"), Err(RleError::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn from_rle_rejects_oversized_header() {
+        assert_eq!(
+            Grid::from_rle("x = 100000, y = 100000\no!").err(),
+            Some(RleError::TooLarge {
+                width: 100000,
+                height: 100000
+            })
+        );
+        let huge = format!("x = {}, y = {}\no!", usize::MAX, usize::MAX);
+        assert!(matches!(Grid::from_rle(&huge), Err(RleError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn from_rle_rejects_runs_that_overflow_the_position() {
+        assert_eq!(
+            Grid::from_rle("x = 3, y = 3\nbo$18446744073709551615$o!").err(),
+            Some(RleError::InvalidRunCount { line: 2 })
+        );
+        assert_eq!(
+            Grid::from_rle("x = 3, y = 3\n2o18446744073709551615bo!").err(),
+            Some(RleError::InvalidRunCount { line: 2 })
+        );
+    }
+
+    #[test]
+    fn from_rle_rejects_unknown_characters() {
+        assert_eq!(
+            Grid::from_rle("x = 3, y = 1\n2oz!").err(),
+            Some(RleError::UnexpectedCharacter { line: 2, character: 'z' })
+        );
+    }
+
+    #[test]
+    fn from_rle_reads_rule_and_multistate_tags() {
+        let grid = Grid::from_rle("x = 3, y = 2, rule = B2/S/C3\nA.B$.2A!").unwrap();
+        assert_eq!(grid.rule(), Rule::BRIANS_BRAIN);
+        assert_eq!(grid.cells, vec![vec![Alive, Dead, Dying(1)], vec![Dead, Alive, Alive]]);
+        assert_eq!(grid.population(), 3);
+    }
+
+    #[test]
+    fn from_rle_rejects_states_the_rule_lacks() {
+        assert_eq!(
+            Grid::from_rle("x = 2, y = 1, rule = B2/S/C3\nAC!").err(),
+            Some(RleError::UnexpectedCharacter { line: 2, character: 'C' })
+        );
+        assert!(matches!(
+            Grid::from_rle("x = 2, y = 1, rule = B3/S23/Q\n2o!"),
+            Err(RleError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn multistate_round_trip_preserves_dying_stages() {
+        let mut grid = Grid::new(30, 2);
+        grid.set_rule(Rule::new(&[2], &[], 40).unwrap());
+        grid.cells[0][0] = Alive;
+        grid.cells[0][3] = Dying(1);
+        grid.cells[1][5] = Dying(30);
+        grid.population = 1;
+
+        let rle = grid.to_rle();
+        assert!(rle.starts_with("x = 30, y = 2, rule = B2/S/C40\n"));
+        assert!(rle.contains("pG"));
+        let restored = Grid::from_rle(&rle).unwrap();
+        assert_eq!(restored.cells, grid.cells);
+        assert_eq!(restored.rule(), grid.rule());
+    }
+
+    #[test]
+    fn from_rle_rejects_cells_outside_header_bounds() {
+        assert!(matches!(Grid::from_rle("x = 2, y = 1\n3o!"), Err(RleError::OutOfBounds { .. })));
+    }
+}
@@ -0,0 +1,233 @@
+//! Birth/survival rules, including the multi-state "Generations" family.
+//!
+//! Rules are written as `B<counts>/S<counts>` with an optional `/C<states>` suffix, e.g. `B3/S23` for Conway's
+//! Life or `B2/S/C3` for Brian's Brain. With more than two states a cell that fails to survive does not die
+//! outright but passes through `states - 2` dying stages, during which it neither counts as a neighbor nor can
+//! be reborn.
+use super::CellState::{self, Dead, Dying};
+use std::fmt;
+use std::str::FromStr;
+
+/// Highest neighbor count any supported neighborhood can produce.
+const MAX_NEIGHBORS: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// Bit `n` is set when a dead cell with `n` alive neighbors is born.
+    birth: u16,
+    /// Bit `n` is set when an alive cell with `n` alive neighbors survives.
+    survival: u16,
+    /// Total number of cell states: dead, alive and `states - 2` dying stages.
+    states: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// The rule isn't of the form `B<counts>/S<counts>[/C<states>]`.
+    Malformed(String),
+    /// A birth or survival count is above the eight neighbors a cell can have.
+    NeighborCountOutOfRange(u8),
+    /// The state count is below two or does not fit into a `u8`.
+    InvalidStateCount(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Malformed(rule) => write!(f, "`{rule}` is not of the form B<counts>/S<counts>[/C<states>]"),
+            RuleError::NeighborCountOutOfRange(count) => write!(f, "neighbor count {count} is out of range 0-8"),
+            RuleError::InvalidStateCount(states) => write!(f, "state count `{states}` must be a number from 2 to 255"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`.
+    pub const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+        states: 2,
+    };
+
+    /// Brian's Brain, `B2/S/C3`: every alive cell dies after one generation and rests for one more.
+    pub const BRIANS_BRAIN: Rule = Rule {
+        birth: 1 << 2,
+        survival: 0,
+        states: 3,
+    };
+
+    /// Build a rule from the neighbor counts that cause a birth or let a cell survive.
+    pub fn new(birth: &[u8], survival: &[u8], states: u8) -> Result<Rule, RuleError> {
+        if states < 2 {
+            return Err(RuleError::InvalidStateCount(states.to_string()));
+        }
+        Ok(Rule {
+            birth: count_mask(birth)?,
+            survival: count_mask(survival)?,
+            states,
+        })
+    }
+
+    /// Number of cell states, 2 for classic two-state rules.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn births_on(&self, alive_neighbors: usize) -> bool {
+        self.birth & (1 << alive_neighbors) != 0
+    }
+
+    pub fn survives_on(&self, alive_neighbors: usize) -> bool {
+        self.survival & (1 << alive_neighbors) != 0
+    }
+
+    /// State following dying stage `stage`, where stage 0 is an alive cell that failed to survive.
+    pub(super) fn decay(&self, stage: u8) -> CellState {
+        if u16::from(stage) + 2 < u16::from(self.states) {
+            Dying(stage + 1)
+        } else {
+            Dead
+        }
+    }
+
+    /// Whether `state` can occur under this rule; dying stages beyond the state count cannot.
+    pub fn allows(&self, state: CellState) -> bool {
+        match state {
+            Dying(stage) => stage >= 1 && u16::from(stage) + 2 <= u16::from(self.states),
+            _ => true,
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+fn count_mask(counts: &[u8]) -> Result<u16, RuleError> {
+    counts.iter().try_fold(0, |mask, &count| {
+        if count > MAX_NEIGHBORS {
+            Err(RuleError::NeighborCountOutOfRange(count))
+        } else {
+            Ok(mask | 1 << count)
+        }
+    })
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    /// Parse `B3/S23` style rules, case-insensitively and in any part order, with an optional `/C<states>`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let malformed = || RuleError::Malformed(text.to_string());
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = None;
+
+        for part in text.trim().split('/') {
+            let mut chars = part.chars();
+            let prefix = chars.next().ok_or_else(malformed)?.to_ascii_uppercase();
+            let value = chars.as_str();
+            let slot = match prefix {
+                'B' => &mut birth,
+                'S' => &mut survival,
+                'C' => {
+                    let count = value.parse::<u8>().map_err(|_| RuleError::InvalidStateCount(value.to_string()))?;
+                    if states.replace(count).is_some() {
+                        return Err(malformed());
+                    }
+                    continue;
+                }
+                _ => return Err(malformed()),
+            };
+            let mut mask = 0;
+            for digit in value.chars() {
+                let count = digit.to_digit(10).ok_or_else(malformed)? as u8;
+                if count > MAX_NEIGHBORS {
+                    return Err(RuleError::NeighborCountOutOfRange(count));
+                }
+                mask |= 1 << count;
+            }
+            if slot.replace(mask).is_some() {
+                return Err(malformed());
+            }
+        }
+
+        let (Some(birth), Some(survival)) = (birth, survival) else {
+            return Err(malformed());
+        };
+        let states = states.unwrap_or(2);
+        if states < 2 {
+            return Err(RuleError::InvalidStateCount(states.to_string()));
+        }
+        Ok(Rule { birth, survival, states })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |mask: u16| {
+            (0..=MAX_NEIGHBORS)
+                .filter(|count| mask & (1 << count) != 0)
+                .map(|count| count.to_string())
+                .collect::<String>()
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::CellState::Alive;
+
+    #[test]
+    fn parses_classic_and_generations_rules() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::CONWAY));
+        assert_eq!("b2/s/c3".parse(), Ok(Rule::BRIANS_BRAIN));
+        assert_eq!("S23/B3".parse(), Ok(Rule::CONWAY));
+        assert_eq!("B36/S23".parse(), Rule::new(&[3, 6], &[2, 3], 2));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for rule in ["B3/S23", "B2/S/C3", "B36/S23", "B/S012345678", "B34/S34/C5"] {
+            assert_eq!(rule.parse::<Rule>().unwrap().to_string(), rule);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        assert_eq!("B3".parse::<Rule>(), Err(RuleError::Malformed("B3".to_string())));
+        assert_eq!("B3/S23/X".parse::<Rule>(), Err(RuleError::Malformed("B3/S23/X".to_string())));
+        assert_eq!("B3/B3/S23".parse::<Rule>(), Err(RuleError::Malformed("B3/B3/S23".to_string())));
+        assert_eq!("B39/S23".parse::<Rule>(), Err(RuleError::NeighborCountOutOfRange(9)));
+        assert_eq!("B3/S23/C1".parse::<Rule>(), Err(RuleError::InvalidStateCount("1".to_string())));
+        assert_eq!("B3/S23/Cx".parse::<Rule>(), Err(RuleError::InvalidStateCount("x".to_string())));
+        assert_eq!(Rule::new(&[9], &[], 2), Err(RuleError::NeighborCountOutOfRange(9)));
+    }
+
+    #[test]
+    fn decay_walks_through_dying_stages() {
+        let rule = Rule::new(&[2], &[], 4).unwrap();
+        assert_eq!(rule.decay(0), Dying(1));
+        assert_eq!(rule.decay(1), Dying(2));
+        assert_eq!(rule.decay(2), Dead);
+        assert_eq!(Rule::CONWAY.decay(0), Dead);
+    }
+
+    #[test]
+    fn allows_only_existing_dying_stages() {
+        assert!(Rule::BRIANS_BRAIN.allows(Dying(1)));
+        assert!(!Rule::BRIANS_BRAIN.allows(Dying(2)));
+        assert!(!Rule::CONWAY.allows(Dying(1)));
+        assert!(Rule::CONWAY.allows(Alive));
+    }
+}
@@ -0,0 +1,156 @@
+//! `serde` support for [`Grid`], enabled by the `serde` feature.
+//!
+//! Only the cells, the generation counter and the simulation settings are stored; the rule is written in its `B3/S23`
+//! form. `next_cells` is scratch space and the population is derived from the cells, so both are rebuilt on
+//! deserialize.
+use super::CellState::{self, Alive};
+use super::{EdgeMode, Grid, Neighborhood, Rule};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+#[serde(rename = "Grid")]
+struct GridRef<'a> {
+    cells: &'a [Vec<CellState>],
+    generation: u64,
+    neighborhood: Neighborhood,
+    edge_mode: EdgeMode,
+    vertical_edge_mode: EdgeMode,
+    rule: Rule,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Grid")]
+struct GridData {
+    cells: Vec<Vec<CellState>>,
+    #[serde(default)]
+    generation: u64,
+    #[serde(default)]
+    neighborhood: Neighborhood,
+    #[serde(default)]
+    edge_mode: EdgeMode,
+    /// Absent from grids saved before the axes could differ, which used `edge_mode` for both.
+    #[serde(default)]
+    vertical_edge_mode: Option<EdgeMode>,
+    #[serde(default)]
+    rule: Rule,
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GridRef {
+            cells: &self.cells,
+            generation: self.generation,
+            neighborhood: self.neighborhood,
+            edge_mode: self.edge_modes.0,
+            vertical_edge_mode: self.edge_modes.1,
+            rule: self.rule,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let GridData {
+            cells,
+            generation,
+            neighborhood,
+            edge_mode,
+            vertical_edge_mode,
+            rule,
+        } = GridData::deserialize(deserializer)?;
+        let width = cells.first().map_or(0, Vec::len);
+        if cells.iter().any(|row| row.len() != width) {
+            return Err(D::Error::custom("grid rows must all have the same length"));
+        }
+
+        if let Some(cell) = cells.iter().flatten().find(|cell| !rule.allows(**cell)) {
+            return Err(D::Error::custom(format!("{cell:?} is not a state of rule {rule}")));
+        }
+
+        let mut grid = Grid::new(width, cells.len());
+        grid.population = cells.iter().flatten().filter(|cell| **cell == Alive).count();
+        grid.cells = cells;
+        grid.generation = generation;
+        grid.neighborhood = neighborhood;
+        grid.edge_modes = (edge_mode, vertical_edge_mode.unwrap_or(edge_mode));
+        grid.rule = rule;
+        Ok(grid)
+    }
+}
+
+impl Serialize for Rule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn serde_round_trip_restores_cells() {
+        let mut grid = Grid::new(24, 16);
+        let mut rng = StdRng::seed_from_u64(99);
+        grid.randomize_with_density_rng(0.5, &mut rng);
+        grid.advance();
+
+        let json = serde_json::to_string(&grid).unwrap();
+        let mut restored: Grid = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.cells, grid.cells);
+        assert_eq!(restored.population(), grid.population());
+        assert_eq!(restored.generation(), grid.generation());
+
+        // The rebuilt scratch buffer must be usable straight away.
+        assert_eq!(restored.advance(), grid.advance());
+        assert_eq!(restored.cells, grid.cells);
+    }
+
+    #[test]
+    fn serde_round_trip_keeps_rule_and_dying_cells() {
+        let mut grid = Grid::new(4, 4);
+        grid.set_rule(Rule::BRIANS_BRAIN);
+        grid.set(1, 1, Alive);
+        grid.set(1, 2, CellState::Dying(1));
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert!(json.contains(r#""rule":"B2/S/C3""#));
+        let restored: Grid = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.rule(), Rule::BRIANS_BRAIN);
+        assert_eq!(restored.cells, grid.cells);
+        assert_eq!(restored.population(), 1);
+
+        // Without a rule the grid falls back to Conway's Life, which has no dying stages.
+        let json = r#"{"cells":[["Dead",{"Dying":1}]]}"#;
+        assert!(serde_json::from_str::<Grid>(json).is_err());
+    }
+
+    #[test]
+    fn serde_round_trip_keeps_per_axis_edge_modes() {
+        let grid = Grid::new(4, 4).with_edge_modes(EdgeMode::Toroidal, EdgeMode::Dead);
+        let json = serde_json::to_string(&grid).unwrap();
+        let restored: Grid = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.edge_modes(), (EdgeMode::Toroidal, EdgeMode::Dead));
+
+        // Older files only have `edge_mode`, which applied to both axes.
+        let json = r#"{"cells":[["Dead","Alive"]],"edge_mode":"Reflect"}"#;
+        let restored: Grid = serde_json::from_str(json).unwrap();
+        assert_eq!(restored.edge_modes(), (EdgeMode::Reflect, EdgeMode::Reflect));
+    }
+
+    #[test]
+    fn deserialize_rejects_ragged_rows() {
+        let json = r#"{"cells":[["Dead","Alive"],["Dead"]],"generation":0}"#;
+        assert!(serde_json::from_str::<Grid>(json).is_err());
+    }
+}
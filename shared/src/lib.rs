@@ -4,153 +4,2250 @@
 * A live cell with more than three live neighbors dies.
 * A dead cell will be brought back to live if it has exactly three live neighbors.
 */
+pub mod colors;
+pub mod patterns;
+
 pub mod grid {
-    use crate::grid::CellState::{Alive, Dead};
-    use rand::Rng;
+    use crate::colors::ColorScheme;
+    use crate::grid::CellState::{Alive, Dead, Dying};
+    use history::Snapshot;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::VecDeque;
+    use std::fmt;
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    /// Grids with fewer cells than this are advanced on the calling thread; splitting them costs more than it saves.
+    #[cfg(feature = "parallel")]
+    const PARALLEL_MIN_CELLS: usize = 128 * 128;
+
+    #[cfg(feature = "bitpacked")]
+    mod bitpacked;
+    mod history;
+    mod plaintext;
+    mod rle;
+    mod rule;
+    #[cfg(feature = "serde")]
+    mod serialization;
+
+    #[cfg(feature = "bitpacked")]
+    pub use bitpacked::BitGrid;
+    pub use plaintext::ParseError;
+    pub use rle::{PatternMeta, RleError};
+    pub use rule::{Rule, RuleError};
 
     #[derive(Debug, PartialEq, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum CellState {
         Dead,
         Alive,
+        /// Refractory stage `n` (counting from 1) of a multi-state rule: the cell is no longer alive, doesn't count
+        /// as a neighbor and can't be reborn until it has decayed to `Dead`.
+        Dying(u8),
+    }
+    /// Which surrounding cells count as neighbors.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Neighborhood {
+        /// All eight surrounding cells.
+        #[default]
+        Moore,
+        /// Only the four orthogonally adjacent cells.
+        VonNeumann,
+        /// The six cells around a hexagon, with the grid read as a hexagonal lattice whose odd rows sit half a cell to
+        /// the right: the cells on either side plus two above and two below, which lean left on even rows and right on
+        /// odd ones. Wrapping top to bottom keeps the lattice intact only on grids with an even number of rows.
+        Hex,
+    }
+
+    /// Result of [`Grid::advance_status`].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum AdvanceOutcome {
+        /// The generation differs from the previous one.
+        Changed,
+        /// Nothing changed: the pattern has reached a fixed point.
+        Static,
+        /// No cells are alive after the step, whether they just died out or the grid was already empty.
+        Extinct,
+    }
+
+    /// What lies beyond the edges of the grid.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum EdgeMode {
+        /// Edges wrap around to the opposite side, making the grid a torus.
+        #[default]
+        Toroidal,
+        /// Everything outside the grid is permanently dead.
+        Dead,
+        /// Edges are mirrors: a position beyond an edge takes the value of its mirror image inside, so a border
+        /// cell sees itself and its neighbors along the border once more.
+        Reflect,
+        /// The grid is surrounded by a border of permanently alive cells.
+        FixedAlive,
+    }
+
+    /// Mirror symmetry of the boards made by [`Grid::randomize_symmetric`].
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Symmetry {
+        /// The bottom half mirrors the top half across the horizontal center line.
+        Horizontal,
+        /// The right half mirrors the left half across the vertical center line.
+        Vertical,
+        /// The top-left quadrant is mirrored across both center lines.
+        FourFold,
+    }
+
+    /// Lets a dead-edged grid grow as its pattern approaches the border, so guns and puffers don't run into the
+    /// edge. Growth stops at `max_width` x `max_height`.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct AutoGrow {
+        /// Alive cells closer than this to an edge make the grid grow on that side.
+        pub margin: usize,
+        pub max_width: usize,
+        pub max_height: usize,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum GridError {
+        /// There are no rows, or the rows have no cells.
+        Empty,
+        /// Row `row` has `found` cells where the first row has `expected`.
+        RaggedRow { row: usize, expected: usize, found: usize },
+        /// A snapshot of a `snapshot` (width, height) board can't be restored into a `grid` sized one.
+        SizeMismatch { snapshot: (usize, usize), grid: (usize, usize) },
+        /// A `found` (width, height) grid can't be compared cell by cell with an `expected` sized one.
+        DimensionMismatch { expected: (usize, usize), found: (usize, usize) },
     }
-    #[derive(Default)]
+
+    impl fmt::Display for GridError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                GridError::Empty => write!(f, "grid has no cells"),
+                GridError::RaggedRow { row, expected, found } => write!(f, "row {row} has {found} cells, expected {expected}"),
+                GridError::SizeMismatch { snapshot, grid } => {
+                    write!(f, "snapshot is {}x{} but the grid is {}x{}", snapshot.0, snapshot.1, grid.0, grid.1)
+                }
+                GridError::DimensionMismatch { expected, found } => {
+                    write!(f, "grid is {}x{}, expected {}x{}", found.0, found.1, expected.0, expected.1)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for GridError {}
+
+    /// Saved cells of a [`Grid`], taken with [`Grid::snapshot`] and put back with [`Grid::restore`]. Encoded like the
+    /// step-back history, so it stays small on mostly dead boards.
+    #[derive(Debug, Clone, Default)]
+    pub struct GridSnapshot(Snapshot);
+
+    #[derive(Debug)]
     pub struct Grid {
+        /// Current generation. Writing to it directly bypasses the population counter;
+        /// prefer the `Grid` methods when the counter matters.
         pub cells: Vec<Vec<CellState>>,
         next_cells: Vec<Vec<CellState>>,
+        population: usize,
+        generation: u64,
+        neighborhood: Neighborhood,
+        /// `(horizontal, vertical)`: what lies beyond the left and right edges, and beyond the top and bottom ones.
+        edge_modes: (EdgeMode, EdgeMode),
+        rule: Rule,
+        /// Run-length encoded previous generations, oldest first, bounded by `history_depth`.
+        history: VecDeque<Snapshot>,
+        history_depth: usize,
+        /// Generations each cell has been alive for, when age tracking is enabled.
+        ages: Option<Vec<Vec<u32>>>,
+        /// Grow the grid before each step when alive cells near a dead edge.
+        auto_grow: Option<AutoGrow>,
+        /// Which rows the most recent `advance` changed, indexed by row.
+        dirty_rows: Vec<bool>,
+        /// Population after each of the most recent generations, oldest first, bounded by `population_history_len`.
+        population_history: VecDeque<usize>,
+        population_history_len: usize,
+    }
+
+    /// The smallest board that doesn't count any neighbor twice: an all-dead 3x3 grid.
+    impl Default for Grid {
+        fn default() -> Self {
+            Grid::new(3, 3)
+        }
     }
 
     impl Grid {
+        /// Create an all-dead grid. Boards should be at least 3x3: on smaller ones the wrapped neighbors of a cell
+        /// overlap and get counted more than once. A grid without cells is allowed but never changes.
         pub fn new(width: usize, height: usize) -> Self {
             Grid {
                 cells: vec![vec![Dead; width]; height],
                 next_cells: vec![vec![Dead; width]; height],
+                population: 0,
+                generation: 0,
+                neighborhood: Neighborhood::Moore,
+                edge_modes: (EdgeMode::Toroidal, EdgeMode::Toroidal),
+                rule: Rule::CONWAY,
+                history: VecDeque::new(),
+                history_depth: 0,
+                ages: None,
+                auto_grow: None,
+                dirty_rows: Vec::new(),
+                population_history: VecDeque::new(),
+                population_history_len: 0,
+            }
+        }
+
+        /// Build a grid from existing rows, which must all have the same non-zero length.
+        pub fn from_cells(cells: Vec<Vec<CellState>>) -> Result<Grid, GridError> {
+            let width = cells.first().map_or(0, Vec::len);
+            if width == 0 {
+                return Err(GridError::Empty);
+            }
+            if let Some((row, found)) = cells.iter().map(Vec::len).enumerate().find(|(_, len)| *len != width) {
+                return Err(GridError::RaggedRow { row, expected: width, found });
+            }
+
+            let mut grid = Grid::new(width, cells.len());
+            grid.population = cells.iter().flatten().filter(|cell| **cell == Alive).count();
+            grid.cells = cells;
+            Ok(grid)
+        }
+
+        /// Create a grid that remembers up to `depth` previous generations for `step_back`.
+        pub fn with_history(width: usize, height: usize, depth: usize) -> Self {
+            Grid {
+                history: VecDeque::with_capacity(depth),
+                history_depth: depth,
+                ..Grid::new(width, height)
+            }
+        }
+
+        /// Number of generations `step_back` can currently undo.
+        pub fn history_len(&self) -> usize {
+            self.history.len()
+        }
+
+        /// Memory taken by the encoded history snapshots, in bytes.
+        pub fn history_bytes(&self) -> usize {
+            self.history.iter().map(Snapshot::encoded_len).sum()
+        }
+
+        /// Restore the previous generation. Returns `false` when there is no history left.
+        pub fn step_back(&mut self) -> bool {
+            let Some(previous) = self.history.pop_back() else {
+                return false;
+            };
+            previous.decode_into(&mut self.cells);
+            self.population = self.cells.iter().flatten().filter(|cell| **cell == Alive).count();
+            self.generation = self.generation.saturating_sub(1);
+            // Ages aren't part of the history, so they restart from the restored generation.
+            self.reset_ages();
+            self.population_history.pop_back();
+            true
+        }
+
+        /// Save the current cells, to return to later with [`restore`](Self::restore).
+        pub fn snapshot(&self) -> GridSnapshot {
+            let mut snapshot = Snapshot::default();
+            snapshot.encode_from(&self.cells);
+            GridSnapshot(snapshot)
+        }
+
+        /// Overwrite the cells with a snapshot of a grid of the same size. Only the cells are restored: the
+        /// generation counter and history carry on, and ages restart as after `step_back`.
+        pub fn restore(&mut self, snapshot: &GridSnapshot) -> Result<(), GridError> {
+            let size = (self.width(), self.height());
+            if snapshot.0.size() != size {
+                return Err(GridError::SizeMismatch {
+                    snapshot: snapshot.0.size(),
+                    grid: size,
+                });
+            }
+            snapshot.0.decode_into(&mut self.cells);
+            self.population = self.cells.iter().flatten().filter(|cell| **cell == Alive).count();
+            self.reset_ages();
+            Ok(())
+        }
+
+        /// Push the generation that `advance` just replaced, which lives in `next_cells` after the swap.
+        fn record_history(&mut self) {
+            if self.history_depth == 0 {
+                return;
+            }
+            // Reuse the oldest snapshot's allocation once the buffer is full.
+            let mut snapshot = if self.history.len() == self.history_depth {
+                self.history.pop_front().unwrap_or_default()
+            } else {
+                Snapshot::default()
+            };
+            snapshot.encode_from(&self.next_cells);
+            self.history.push_back(snapshot);
+        }
+
+        /// Record the population after each of the next `len` generations, for charting how a pattern settles; once
+        /// `len` are recorded the oldest make way. 0 turns recording off and drops what was recorded.
+        pub fn set_population_history(&mut self, len: usize) {
+            self.population_history_len = len;
+            let excess = self.population_history.len().saturating_sub(len);
+            self.population_history.drain(..excess);
+        }
+
+        /// Population after each recorded generation, oldest first. Starts over whenever the board is cleared or
+        /// randomized.
+        pub fn population_history(&self) -> &VecDeque<usize> {
+            &self.population_history
+        }
+
+        fn record_population(&mut self) {
+            if self.population_history_len == 0 {
+                return;
+            }
+            if self.population_history.len() == self.population_history_len {
+                self.population_history.pop_front();
+            }
+            self.population_history.push_back(self.population);
+        }
+
+        pub fn rule(&self) -> Rule {
+            self.rule
+        }
+
+        /// Select the rule used by the next `advance`. Dying stages the new rule doesn't have become `Dead`.
+        pub fn set_rule(&mut self, rule: Rule) {
+            self.rule = rule;
+            for cell in self.cells.iter_mut().flatten() {
+                if !rule.allows(*cell) {
+                    *cell = Dead;
+                }
+            }
+        }
+
+        /// Start or stop tracking how long each cell has been alive. Enabling starts every cell at age 0.
+        pub fn set_age_tracking(&mut self, enabled: bool) {
+            if !enabled {
+                self.ages = None;
+            } else if self.ages.is_none() {
+                self.ages = Some(self.cells.iter().map(|row| vec![0; row.len()]).collect());
+            }
+        }
+
+        pub fn tracks_ages(&self) -> bool {
+            self.ages.is_some()
+        }
+
+        /// Generations the cell has survived since it was born, counted like `generation`.
+        /// Dead and newly born cells are age 0; returns `None` unless age tracking is enabled.
+        pub fn age(&self, row: usize, col: usize) -> Option<u32> {
+            self.ages.as_ref().map(|ages| ages[row][col])
+        }
+
+        fn reset_ages(&mut self) {
+            if let Some(ages) = &mut self.ages {
+                *ages = self.cells.iter().map(|row| vec![0; row.len()]).collect();
+            }
+        }
+
+        /// Age survivors and reset everything else, comparing `cells` with the replaced generation in `next_cells`.
+        fn update_ages(&mut self) {
+            let Some(ages) = &mut self.ages else {
+                return;
+            };
+            for ((age_row, row), previous_row) in ages.iter_mut().zip(&self.cells).zip(&self.next_cells) {
+                for ((age, cell), previous) in age_row.iter_mut().zip(row).zip(previous_row) {
+                    *age = match (previous, cell) {
+                        (Alive, Alive) => age.saturating_add(1),
+                        _ => 0,
+                    };
+                }
+            }
+        }
+
+        /// Number of columns; 0 when the grid has no rows.
+        pub fn width(&self) -> usize {
+            self.cells.first().map_or(0, Vec::len)
+        }
+
+        /// Number of rows.
+        pub fn height(&self) -> usize {
+            self.cells.len()
+        }
+
+        /// Number of alive cells, maintained incrementally.
+        pub fn population(&self) -> usize {
+            self.population
+        }
+
+        /// Number of generations that changed the grid since the last `new`, `clear` or `randomize`.
+        /// A static pattern makes `advance` return `false`, which does not count as a generation.
+        pub fn generation(&self) -> u64 {
+            self.generation
+        }
+
+        pub fn neighborhood(&self) -> Neighborhood {
+            self.neighborhood
+        }
+
+        /// Select the neighborhood used by the next `advance`.
+        pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) {
+            self.neighborhood = neighborhood;
+        }
+
+        /// `(horizontal, vertical)` edge modes, see [`set_edge_modes`](Self::set_edge_modes).
+        pub fn edge_modes(&self) -> (EdgeMode, EdgeMode) {
+            self.edge_modes
+        }
+
+        /// Select how the next `advance` treats all four edges; the cells themselves are left alone.
+        pub fn set_edge_mode(&mut self, edge_mode: EdgeMode) {
+            self.set_edge_modes(edge_mode, edge_mode);
+        }
+
+        /// Like [`set_edge_mode`](Self::set_edge_mode), with `horizontal` applying beyond the left and right edges
+        /// and `vertical` beyond the top and bottom ones. A `Toroidal` horizontal and `Dead` vertical mode make the
+        /// grid a cylinder.
+        pub fn set_edge_modes(&mut self, horizontal: EdgeMode, vertical: EdgeMode) {
+            self.edge_modes = (horizontal, vertical);
+        }
+
+        /// This grid with the given per-axis edge modes, as set by [`set_edge_modes`](Self::set_edge_modes).
+        pub fn with_edge_modes(mut self, horizontal: EdgeMode, vertical: EdgeMode) -> Self {
+            self.set_edge_modes(horizontal, vertical);
+            self
+        }
+
+        pub fn auto_grow(&self) -> Option<AutoGrow> {
+            self.auto_grow
+        }
+
+        /// Enable or disable growing the grid as alive cells approach its edges. Only takes effect along axes with
+        /// [`EdgeMode::Dead`] edges; a toroidal axis has no edges to run into.
+        pub fn set_auto_grow(&mut self, auto_grow: Option<AutoGrow>) {
+            self.auto_grow = auto_grow;
+        }
+
+        /// Add dead rows and columns on every side where an alive cell is within the auto-grow margin, leaving
+        /// twice the margin free so the grid doesn't have to grow again right away.
+        fn grow_to_fit(&mut self) {
+            let Some(AutoGrow {
+                margin,
+                max_width,
+                max_height,
+            }) = self.auto_grow
+            else {
+                return;
+            };
+            let (horizontal, vertical) = self.edge_modes;
+            if horizontal != EdgeMode::Dead && vertical != EdgeMode::Dead {
+                return;
+            }
+            let Some((min_row, min_col, max_row, max_col)) = self.alive_bounds() else {
+                return;
+            };
+            let (width, height) = (self.width(), self.height());
+            // Only axes with dead edges grow.
+            let budget = |mode: EdgeMode, max: usize, len: usize| if mode == EdgeMode::Dead { max.saturating_sub(len) } else { 0 };
+            // Room wanted on each side, handed out from what is left below the maximum size.
+            let wanted = |distance: usize| if distance < margin { 2 * margin - distance } else { 0 };
+            let split = |before: usize, after: usize, budget: usize| {
+                let before = before.min(budget);
+                (before, after.min(budget - before))
+            };
+            let vertical_budget = budget(vertical, max_height, height);
+            let (mut top, bottom) = split(wanted(min_row), wanted(height - 1 - max_row), vertical_budget);
+            // Hex rows lean by parity, so an odd number of rows on top would change every cell's neighbors.
+            if self.neighborhood == Neighborhood::Hex && !top.is_multiple_of(2) {
+                top = if top + bottom < vertical_budget { top + 1 } else { top - 1 };
+            }
+            let (left, right) = split(wanted(min_col), wanted(width - 1 - max_col), budget(horizontal, max_width, width));
+            if top + bottom + left + right == 0 {
+                return;
+            }
+
+            let new_width = width + left + right;
+            let new_height = height + top + bottom;
+            self.cells = pad(&self.cells, Dead, top, left, new_width, new_height);
+            self.next_cells = vec![vec![Dead; new_width]; new_height];
+            if let Some(ages) = &mut self.ages {
+                *ages = pad(ages, 0, top, left, new_width, new_height);
+            }
+            // Snapshots of the old dimensions can't be restored any more.
+            self.history.clear();
+        }
+
+        /// Iterate over the `(row, col)` of every alive cell in row-major order.
+        pub fn alive_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+            self.cells.iter().enumerate().flat_map(|(row_index, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, cell)| **cell == Alive)
+                    .map(move |(col_index, _)| (row_index, col_index))
+            })
+        }
+
+        /// Smallest rectangle containing every alive cell as `(min_row, min_col, max_row, max_col)`, inclusive.
+        /// Returns `None` when no cell is alive.
+        pub fn alive_bounds(&self) -> Option<(usize, usize, usize, usize)> {
+            if self.population == 0 {
+                return None;
+            }
+            let mut bounds: Option<(usize, usize, usize, usize)> = None;
+            for (row_index, row) in self.cells.iter().enumerate() {
+                let Some(first) = row.iter().position(|cell| *cell == Alive) else {
+                    continue;
+                };
+                let last = row.iter().rposition(|cell| *cell == Alive).unwrap_or(first);
+                bounds = Some(match bounds {
+                    None => (row_index, first, row_index, last),
+                    Some((min_row, min_col, _, max_col)) => (min_row, min_col.min(first), row_index, max_col.max(last)),
+                });
+            }
+            bounds
+        }
+
+        /// Whether any alive cell sits in the first or last row or column, where it interacts with whatever lies
+        /// beyond the edges. Only the border is scanned, unlike [`alive_bounds`](Self::alive_bounds).
+        pub fn touches_border(&self) -> bool {
+            let (Some(first_row), Some(last_row)) = (self.cells.first(), self.cells.last()) else {
+                return false;
+            };
+            let is_alive = |cell: &CellState| *cell == Alive;
+            self.population > 0
+                && (first_row.iter().any(is_alive)
+                    || last_row.iter().any(is_alive)
+                    || self
+                        .cells
+                        .iter()
+                        .any(|row| row.first().is_some_and(is_alive) || row.last().is_some_and(is_alive)))
+        }
+
+        /// Number of alive cells in the `height` x `width` rectangle whose top-left cell is `top`, `left`. The
+        /// rectangle is clipped to the grid, so parts of it beyond the edges simply count nothing.
+        pub fn count_alive_in_region(&self, top: usize, left: usize, height: usize, width: usize) -> usize {
+            let bottom = top.saturating_add(height).min(self.height());
+            let right = left.saturating_add(width).min(self.width());
+            if top >= bottom || left >= right {
+                return 0;
+            }
+            self.cells[top..bottom]
+                .iter()
+                .map(|row| row[left..right].iter().filter(|cell| **cell == Alive).count())
+                .sum()
+        }
+
+        /// Kill every cell and reset the generation counter and history.
+        pub fn clear(&mut self) {
+            for row in self.cells.iter_mut() {
+                row.fill(Dead);
+            }
+            self.population = 0;
+            self.generation = 0;
+            self.history.clear();
+            self.population_history.clear();
+            self.reset_ages();
+        }
+
+        /// Set a single cell, keeping the population counter in sync.
+        pub fn set(&mut self, row: usize, col: usize, state: CellState) {
+            let cell = &mut self.cells[row][col];
+            match (*cell == Alive, state == Alive) {
+                (false, true) => self.population += 1,
+                (true, false) => self.population -= 1,
+                _ => {}
+            }
+            if *cell != state {
+                if let Some(ages) = &mut self.ages {
+                    ages[row][col] = 0;
+                }
+            }
+            *cell = state;
+        }
+
+        /// Flip a single cell between alive and dead.
+        pub fn toggle(&mut self, row: usize, col: usize) {
+            let state = if self.cells[row][col] == Alive { Dead } else { Alive };
+            self.set(row, col, state);
+        }
+
+        /// Set every cell of the `height` x `width` rectangle whose top-left cell is `top`, `left` to `state`, e.g.
+        /// to build a wall or clear a zone. The rectangle is clipped to the grid like in
+        /// [`count_alive_in_region`](Self::count_alive_in_region).
+        pub fn fill_region(&mut self, top: usize, left: usize, height: usize, width: usize, state: CellState) {
+            let bottom = top.saturating_add(height).min(self.height());
+            let right = left.saturating_add(width).min(self.width());
+            for row in top..bottom {
+                for col in left..right {
+                    self.set(row, col, state);
+                }
+            }
+        }
+
+        /// Change the grid dimensions, keeping the overlapping top-left region.
+        /// New cells start `Dead` and cells outside the new bounds are dropped.
+        pub fn resize(&mut self, new_width: usize, new_height: usize) {
+            let mut cells = vec![vec![Dead; new_width]; new_height];
+            for (new_row, old_row) in cells.iter_mut().zip(&self.cells) {
+                let overlap = new_width.min(old_row.len());
+                new_row[..overlap].copy_from_slice(&old_row[..overlap]);
+            }
+
+            self.population = cells.iter().flatten().filter(|cell| **cell == Alive).count();
+            self.cells = cells;
+            self.next_cells = vec![vec![Dead; new_width]; new_height];
+            // Snapshots of the old dimensions can't be restored any more.
+            self.history.clear();
+            self.dirty_rows.clear();
+            self.reset_ages();
+        }
+
+        /// OR the alive cells of `pattern` into this grid with its top-left corner at the given offset.
+        /// Parts of the pattern that fall outside the grid are clipped.
+        pub fn stamp(&mut self, pattern: &Grid, row_offset: usize, col_offset: usize) {
+            let target_rows = self.cells.iter_mut().skip(row_offset);
+            for (target_row, pattern_row) in target_rows.zip(&pattern.cells) {
+                let target_cells = target_row.iter_mut().skip(col_offset);
+                for (target, source) in target_cells.zip(pattern_row) {
+                    if *source == Alive && *target != Alive {
+                        *target = Alive;
+                        self.population += 1;
+                    }
+                }
+            }
+        }
+
+        /// A copy turned clockwise by `quarter_turns` quarter turns, so a pattern can be [`stamp`](Self::stamp)ed
+        /// facing any direction. Odd turns swap the width and height, and the edge modes along with them. Like
+        /// [`next_generation`](Self::next_generation), the copy starts without history or age tracking.
+        pub fn rotated(&self, quarter_turns: u8) -> Grid {
+            let (width, height) = (self.width(), self.height());
+            match quarter_turns % 4 {
+                0 => self.transformed(false, |row, col| (row, col)),
+                1 => self.transformed(true, |row, col| (height - 1 - col, row)),
+                2 => self.transformed(false, |row, col| (height - 1 - row, width - 1 - col)),
+                _ => self.transformed(true, |row, col| (col, width - 1 - row)),
+            }
+        }
+
+        /// A mirrored copy: left to right when `horizontal` is true, top to bottom otherwise.
+        pub fn flipped(&self, horizontal: bool) -> Grid {
+            let (width, height) = (self.width(), self.height());
+            if horizontal {
+                self.transformed(false, |row, col| (row, width - 1 - col))
+            } else {
+                self.transformed(false, |row, col| (height - 1 - row, col))
+            }
+        }
+
+        /// A grid whose cell at `(row, col)` is this grid's cell at `source(row, col)`, with the same rule and
+        /// neighborhood. `swap_axes` swaps the width and height, and the edge modes with them.
+        fn transformed(&self, swap_axes: bool, source: impl Fn(usize, usize) -> (usize, usize)) -> Grid {
+            let mut grid = if swap_axes {
+                Grid::new(self.height(), self.width())
+            } else {
+                Grid::new(self.width(), self.height())
+            };
+            for (row, cells) in grid.cells.iter_mut().enumerate() {
+                for (col, cell) in cells.iter_mut().enumerate() {
+                    let (source_row, source_col) = source(row, col);
+                    *cell = self.cells[source_row][source_col];
+                }
+            }
+            grid.population = self.population;
+            grid.generation = self.generation;
+            grid.neighborhood = self.neighborhood;
+            grid.edge_modes = if swap_axes {
+                (self.edge_modes.1, self.edge_modes.0)
+            } else {
+                self.edge_modes
+            };
+            grid.rule = self.rule;
+            grid
+        }
+
+        /// Fill the grid with a random pattern where each cell is alive with probability 0.5.
+        pub fn randomize(&mut self) {
+            self.randomize_with_density(0.5);
+        }
+
+        /// Fill the grid with the 50% random pattern derived from `seed`; the same seed and grid size always
+        /// produce the same board, so interesting starts can be shared by number.
+        pub fn randomize_seeded(&mut self, seed: u64) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            self.randomize_with_density_rng(0.5, &mut rng);
+        }
+
+        /// Fill the grid with a random pattern where each cell is alive with probability `density`.
+        /// The density is clamped to `0.0..=1.0`; NaN counts as 0.
+        pub fn randomize_with_density(&mut self, density: f64) {
+            let mut rng = rand::rng();
+            self.randomize_with_density_rng(density, &mut rng);
+        }
+
+        /// Like `randomize_with_density`, drawing from `rng` so a seeded generator reproduces the same board.
+        pub fn randomize_with_density_rng<R: Rng + ?Sized>(&mut self, density: f64, rng: &mut R) {
+            let density = if density.is_nan() { 0.0 } else { density.clamp(0.0, 1.0) };
+            let mut population = 0;
+            for row in self.cells.iter_mut() {
+                for cell in row.iter_mut() {
+                    *cell = if rng.random_bool(density) { Alive } else { Dead };
+                    if *cell == Alive {
+                        population += 1;
+                    }
+                }
+            }
+            self.population = population;
+            self.generation = 0;
+            self.history.clear();
+            self.population_history.clear();
+            self.reset_ages();
+        }
+
+        /// Make each cell of the `height` x `width` rectangle whose top-left cell is `top`, `left` alive with probability
+        /// `density` and dead otherwise, leaving the rest of the board alone. The rectangle is clipped to the grid and
+        /// the density clamped as in [`fill_region`](Self::fill_region) and
+        /// [`randomize_with_density`](Self::randomize_with_density).
+        pub fn randomize_region<R: Rng + ?Sized>(&mut self, top: usize, left: usize, height: usize, width: usize, density: f64, rng: &mut R) {
+            let density = if density.is_nan() { 0.0 } else { density.clamp(0.0, 1.0) };
+            let bottom = top.saturating_add(height).min(self.height());
+            let right = left.saturating_add(width).min(self.width());
+            for row in top..bottom {
+                for col in left..right {
+                    let state = if rng.random_bool(density) { Alive } else { Dead };
+                    self.set(row, col, state);
+                }
+            }
+        }
+
+        /// Fill one half or quadrant of the grid with a 50% random pattern from `rng` and mirror it into the rest, for
+        /// starts that keep their symmetry as they evolve.
+        pub fn randomize_symmetric<R: Rng + ?Sized>(&mut self, symmetry: Symmetry, rng: &mut R) {
+            let (width, height) = (self.width(), self.height());
+            let mirror_rows = symmetry != Symmetry::Vertical;
+            let mirror_cols = symmetry != Symmetry::Horizontal;
+            let source = |index: usize, len: usize, mirror: bool| if mirror { index.min(len - 1 - index) } else { index };
+            for row in 0..height {
+                for col in 0..width {
+                    // Mirror images lie above or to the left, so they are always filled first.
+                    let (source_row, source_col) = (source(row, height, mirror_rows), source(col, width, mirror_cols));
+                    self.cells[row][col] = if (source_row, source_col) == (row, col) {
+                        if rng.random_bool(0.5) {
+                            Alive
+                        } else {
+                            Dead
+                        }
+                    } else {
+                        self.cells[source_row][source_col]
+                    };
+                }
+            }
+            self.population = self.cells.iter().flatten().filter(|cell| **cell == Alive).count();
+            self.generation = 0;
+            self.history.clear();
+            self.population_history.clear();
+            self.reset_ages();
+        }
+
+        /// Advance the grid by one step (Game of Life logic).
+        /// Returns `false` without bumping the generation counter when nothing changed.
+        pub fn advance(&mut self) -> bool {
+            if self.has_no_cells() {
+                return false;
+            }
+            self.grow_to_fit();
+            // Take the scratch buffer out so rows can be written while `self` is borrowed immutably.
+            let mut next_cells = std::mem::take(&mut self.next_cells);
+            let (births, deaths) = self.compute_next(&mut next_cells);
+            self.next_cells = next_cells;
+
+            if !self.mark_dirty_rows() {
+                return false;
+            }
+            self.commit_next(births, deaths);
+            true
+        }
+
+        /// Advance up to `steps` generations, stopping early once the pattern stops changing. Returns the number of
+        /// generations that changed the grid.
+        pub fn advance_by(&mut self, steps: usize) -> usize {
+            (0..steps).take_while(|_| self.advance()).count()
+        }
+
+        /// Advance like [`advance`](Self::advance), but tell a fixed point apart from extinction.
+        pub fn advance_status(&mut self) -> AdvanceOutcome {
+            let changed = self.advance();
+            if self.population == 0 {
+                AdvanceOutcome::Extinct
+            } else if changed {
+                AdvanceOutcome::Changed
+            } else {
+                AdvanceOutcome::Static
+            }
+        }
+
+        /// Like [`advance`](Self::advance), but returns the `(row, col, new_state)` of every cell that changed,
+        /// in row-major order, so renderers can update just those. An empty list means nothing changed and the
+        /// generation stays put.
+        pub fn advance_with_changes(&mut self) -> Vec<(usize, usize, CellState)> {
+            if self.has_no_cells() {
+                return Vec::new();
+            }
+            self.grow_to_fit();
+            let mut next_cells = std::mem::take(&mut self.next_cells);
+            let (births, deaths) = self.compute_next(&mut next_cells);
+            self.next_cells = next_cells;
+            self.mark_dirty_rows();
+
+            let changes: Vec<_> = self
+                .cells
+                .iter()
+                .zip(&self.next_cells)
+                .enumerate()
+                .flat_map(|(row_index, (row, next_row))| {
+                    row.iter()
+                        .zip(next_row)
+                        .enumerate()
+                        .filter(|(_, (cell, next_cell))| cell != next_cell)
+                        .map(move |(col_index, (_, next_cell))| (row_index, col_index, *next_cell))
+                })
+                .collect();
+            if !changes.is_empty() {
+                self.commit_next(births, deaths);
+            }
+            changes
+        }
+
+        /// The `(row, col)` of every cell in which `other` disagrees with this grid, in row-major order; empty when
+        /// the cells match. Only the cells are compared, not the rule or generation. Fails with
+        /// [`GridError::DimensionMismatch`] when `other` is a different size.
+        pub fn difference(&self, other: &Grid) -> Result<Vec<(usize, usize)>, GridError> {
+            let (expected, found) = ((self.width(), self.height()), (other.width(), other.height()));
+            if expected != found {
+                return Err(GridError::DimensionMismatch { expected, found });
+            }
+            Ok(self
+                .cells
+                .iter()
+                .zip(&other.cells)
+                .enumerate()
+                .flat_map(|(row_index, (row, other_row))| {
+                    row.iter()
+                        .zip(other_row)
+                        .enumerate()
+                        .filter(|(_, (cell, other_cell))| cell != other_cell)
+                        .map(move |(col_index, _)| (row_index, col_index))
+                })
+                .collect())
+        }
+
+        /// Rows the most recent [`advance`](Self::advance) changed, indexed by row, so renderers can skip the rest.
+        /// Empty before the first step and after a resize.
+        pub fn dirty_rows(&self) -> &[bool] {
+            &self.dirty_rows
+        }
+
+        /// Compare `cells` with `next_cells` row by row, returning whether any row changed.
+        fn mark_dirty_rows(&mut self) -> bool {
+            self.dirty_rows.clear();
+            self.dirty_rows
+                .extend(self.cells.iter().zip(&self.next_cells).map(|(row, next_row)| row != next_row));
+            self.dirty_rows.contains(&true)
+        }
+
+        /// Color of every cell under `scheme`, row by row, passing ages when they are tracked.
+        pub fn map_colors<S: ColorScheme + ?Sized>(&self, scheme: &S) -> Vec<Vec<[f32; 3]>> {
+            let states = self.rule.states();
+            self.cells
+                .iter()
+                .enumerate()
+                .map(|(row_index, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(col_index, cell)| scheme.color(*cell, self.age(row_index, col_index), states))
+                        .collect()
+                })
+                .collect()
+        }
+
+        /// Zero rows or zero-length rows; the neighbor wrapping math would underflow on these.
+        fn has_no_cells(&self) -> bool {
+            self.width() == 0
+        }
+
+        /// The grid `advance` would turn this one into, with the same rule, neighborhood and edge mode, leaving `self`
+        /// untouched. The new grid starts without history or age tracking. Auto-grow is ignored: the new grid keeps
+        /// this one's size, so near a dead edge it can differ from what `advance` would produce after growing.
+        pub fn next_generation(&self) -> Grid {
+            let mut next = Grid::new(self.width(), self.height());
+            let (births, deaths) = self.compute_next(&mut next.cells);
+            next.population = self.population + births - deaths;
+            next.generation = if next.cells == self.cells {
+                self.generation
+            } else {
+                self.generation + 1
+            };
+            next.neighborhood = self.neighborhood;
+            next.edge_modes = self.edge_modes;
+            next.rule = self.rule;
+            next
+        }
+
+        /// This grid followed by each generation after it, computed lazily with
+        /// [`next_generation`](Self::next_generation). The sequence ends once the pattern is static, so a still life
+        /// yields only itself while an oscillator or spaceship goes on forever; bound it with e.g. `take`.
+        pub fn generations(self) -> impl Iterator<Item = Grid> {
+            std::iter::successors(Some(self), |grid| {
+                let next = grid.next_generation();
+                (next.cells != grid.cells).then_some(next)
+            })
+        }
+
+        /// Make `next_cells` the current generation.
+        fn commit_next(&mut self, births: usize, deaths: usize) {
+            std::mem::swap(&mut self.cells, &mut self.next_cells);
+            self.record_history();
+            self.update_ages();
+            self.population = self.population + births - deaths;
+            self.generation += 1;
+            self.record_population();
+        }
+
+        /// Write the next generation into `next_cells`, returning the number of births and deaths.
+        fn compute_next(&self, next_cells: &mut [Vec<CellState>]) -> (usize, usize) {
+            debug_assert!(
+                next_cells.len() == self.cells.len() && next_cells.iter().zip(&self.cells).all(|(next, row)| next.len() == row.len()),
+                "next_cells must have the same dimensions as cells"
+            );
+            #[cfg(feature = "parallel")]
+            if self.width() * self.height() >= PARALLEL_MIN_CELLS {
+                return self.compute_next_parallel(next_cells);
+            }
+            self.compute_next_serial(next_cells)
+        }
+
+        fn compute_next_serial(&self, next_cells: &mut [Vec<CellState>]) -> (usize, usize) {
+            next_cells
+                .iter_mut()
+                .enumerate()
+                .map(|(row_index, next_row)| self.compute_next_row(row_index, next_row))
+                .fold((0, 0), |(births, deaths), (row_births, row_deaths)| {
+                    (births + row_births, deaths + row_deaths)
+                })
+        }
+
+        /// Rows are independent: each one only reads `cells` and writes its own slice of `next_cells`.
+        #[cfg(feature = "parallel")]
+        fn compute_next_parallel(&self, next_cells: &mut [Vec<CellState>]) -> (usize, usize) {
+            use rayon::prelude::*;
+
+            next_cells
+                .par_iter_mut()
+                .enumerate()
+                .map(|(row_index, next_row)| self.compute_next_row(row_index, next_row))
+                .reduce(
+                    || (0, 0),
+                    |(births, deaths), (row_births, row_deaths)| (births + row_births, deaths + row_deaths),
+                )
+        }
+
+        fn compute_next_row(&self, row_index: usize, next_row: &mut [CellState]) -> (usize, usize) {
+            let mut births = 0;
+            let mut deaths = 0;
+            let width = next_row.len();
+
+            if row_index == 0 || row_index + 1 >= self.height() || width < 3 {
+                for (col_index, next_cell) in next_row.iter_mut().enumerate() {
+                    let alive_neighbors = self.alive_neighbors(row_index, col_index);
+                    *next_cell = self.next_state(self.cells[row_index][col_index], alive_neighbors, &mut births, &mut deaths);
+                }
+                return (births, deaths);
+            }
+
+            // Only the first and last column touch an edge; the cells between them skip the wrap checks
+            let last = width - 1;
+            for col_index in [0, last] {
+                let alive_neighbors = self.alive_neighbors(row_index, col_index);
+                next_row[col_index] = self.next_state(self.cells[row_index][col_index], alive_neighbors, &mut births, &mut deaths);
+            }
+            for (col_index, next_cell) in next_row[..last].iter_mut().enumerate().skip(1) {
+                let alive_neighbors = self.interior_alive_neighbors(row_index, col_index);
+                *next_cell = self.next_state(self.cells[row_index][col_index], alive_neighbors, &mut births, &mut deaths);
+            }
+
+            (births, deaths)
+        }
+
+        /// Apply the rule to one cell, counting births and deaths; with more than two states a dying cell decays
+        /// regardless of its neighbors.
+        fn next_state(&self, state: CellState, alive_neighbors: usize, births: &mut usize, deaths: &mut usize) -> CellState {
+            match state {
+                Alive if self.rule.survives_on(alive_neighbors) => Alive, // Survives
+                Alive => {
+                    // Dies, possibly entering the first dying stage
+                    *deaths += 1;
+                    self.rule.decay(0)
+                }
+                Dying(stage) => self.rule.decay(stage),
+                Dead if self.rule.births_on(alive_neighbors) => {
+                    // Becomes alive
+                    *births += 1;
+                    Alive
+                }
+                Dead => Dead, // Remains dead
+            }
+        }
+
+        /// Advance up to `max_period` generations looking for the current state to reappear.
+        /// Returns the cycle length (1 for a still life, 2 for a blinker, ...) and always restores the grid afterwards.
+        pub fn detect_cycle(&mut self, max_period: usize) -> Option<usize> {
+            let start_cells = self.cells.clone();
+            let next_cells = self.next_cells.clone();
+            let start_population = self.population;
+            let start_generation = self.generation;
+            let start_hash = self.cells_hash();
+            let history = std::mem::take(&mut self.history);
+            let history_depth = std::mem::replace(&mut self.history_depth, 0);
+            let ages = self.ages.take();
+            let dirty_rows = std::mem::take(&mut self.dirty_rows);
+            let population_history_len = std::mem::replace(&mut self.population_history_len, 0);
+            // Growing would change the dimensions the start state is compared against.
+            let auto_grow = self.auto_grow.take();
+
+            let mut period = None;
+            for step in 1..=max_period {
+                if !self.advance() {
+                    // A still life only counts when it is the state we started from.
+                    period = (step == 1).then_some(1);
+                    break;
+                }
+                if self.cells_hash() == start_hash && self.cells == start_cells {
+                    period = Some(step);
+                    break;
+                }
+            }
+
+            self.cells = start_cells;
+            self.next_cells = next_cells;
+            self.population = start_population;
+            self.generation = start_generation;
+            self.history = history;
+            self.history_depth = history_depth;
+            self.ages = ages;
+            self.dirty_rows = dirty_rows;
+            self.population_history_len = population_history_len;
+            self.auto_grow = auto_grow;
+            period
+        }
+
+        fn cells_hash(&self) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            self.hash_cells(&mut hasher);
+            hasher.finish()
+        }
+
+        /// Feed the alive cells packed 64 to a word, which is far cheaper than hashing every `CellState`.
+        fn hash_cells<H: Hasher>(&self, hasher: &mut H) {
+            for row in &self.cells {
+                for chunk in row.chunks(64) {
+                    let word = chunk
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, cell)| **cell == Alive)
+                        .fold(0u64, |word, (bit, _)| word | (1 << bit));
+                    hasher.write_u64(word);
+                }
+            }
+        }
+
+        /// Alive neighbors of every cell under the current neighborhood and edge wrapping, e.g. for an overlay
+        /// explaining the next generation.
+        pub fn neighbor_counts(&self) -> Vec<Vec<u8>> {
+            (0..self.height())
+                .map(|row| (0..self.width()).map(|col| self.alive_neighbors(row, col) as u8).collect())
+                .collect()
+        }
+
+        /// Render the board as text, one line per row: `#` for alive cells and `.` for dead or dying ones.
+        pub fn to_ascii(&self) -> String {
+            self.render_text('#', '.')
+        }
+
+        /// Denser rendering for terminals, with a full block for alive cells and a space for the rest.
+        pub fn to_unicode(&self) -> String {
+            self.render_text('█', ' ')
+        }
+
+        fn render_text(&self, alive: char, dead: char) -> String {
+            let mut text = String::with_capacity((self.width() + 1) * self.height());
+            for row in &self.cells {
+                text.extend(row.iter().map(|&cell| if cell == Alive { alive } else { dead }));
+                text.push('\n');
+            }
+            text
+        }
+
+        /// Count the number of alive neighbors for a cell
+        fn alive_neighbors(&self, row: usize, col: usize) -> usize {
+            let height = self.height();
+            let width = self.width();
+            let mut count = 0;
+
+            // Neighbor rows and columns, each axis following its own edge mode; `None` lies beyond an edge that
+            // neither wraps nor reflects
+            let (horizontal, vertical) = self.edge_modes;
+            let beyond = |edge_mode: EdgeMode, wrapped: usize, mirrored: usize| match edge_mode {
+                EdgeMode::Toroidal => Some(wrapped),
+                EdgeMode::Reflect => Some(mirrored),
+                EdgeMode::Dead | EdgeMode::FixedAlive => None,
+            };
+            let top = if row > 0 { Some(row - 1) } else { beyond(vertical, height - 1, row) };
+            let bottom = if row + 1 < height { Some(row + 1) } else { beyond(vertical, 0, row) };
+            let left = if col > 0 { Some(col - 1) } else { beyond(horizontal, width - 1, col) };
+            let right = if col + 1 < width { Some(col + 1) } else { beyond(horizontal, 0, col) };
+            // Beyond a corner both borders meet; either one being alive makes the cell there alive.
+            let alive_beyond_rows = vertical == EdgeMode::FixedAlive;
+            let alive_beyond_cols = horizontal == EdgeMode::FixedAlive;
+            let alive = |row: Option<usize>, col: Option<usize>| match (row, col) {
+                (Some(row), Some(col)) => self.cells[row][col] == Alive,
+                (None, Some(_)) => alive_beyond_rows,
+                (Some(_), None) => alive_beyond_cols,
+                (None, None) => alive_beyond_rows || alive_beyond_cols,
+            };
+
+            // Unrolled neighbor checks for better performance; orthogonal neighbors are part of every neighborhood
+            if alive(top, Some(col)) {
+                count += 1;
+            }
+            if alive(Some(row), left) {
+                count += 1;
+            }
+            if alive(Some(row), right) {
+                count += 1;
+            }
+            if alive(bottom, Some(col)) {
+                count += 1;
+            }
+
+            match self.neighborhood {
+                Neighborhood::Moore => {
+                    if alive(top, left) {
+                        count += 1;
+                    }
+                    if alive(top, right) {
+                        count += 1;
+                    }
+                    if alive(bottom, left) {
+                        count += 1;
+                    }
+                    if alive(bottom, right) {
+                        count += 1;
+                    }
+                }
+                Neighborhood::VonNeumann => {}
+                // The rows above and below are shifted half a cell against this one, towards the left on even rows.
+                Neighborhood::Hex => {
+                    let side = if row.is_multiple_of(2) { left } else { right };
+                    if alive(top, side) {
+                        count += 1;
+                    }
+                    if alive(bottom, side) {
+                        count += 1;
+                    }
+                }
+            }
+
+            count
+        }
+
+        /// Same as [`Grid::alive_neighbors`] for a cell off the border, whose neighbors never wrap or fall off
+        /// the board. Counts without branching so the inner loop of `advance` stays predictable.
+        fn interior_alive_neighbors(&self, row: usize, col: usize) -> usize {
+            let (above, middle, below) = (&self.cells[row - 1], &self.cells[row], &self.cells[row + 1]);
+            let alive = |cells: &[CellState], col: usize| usize::from(cells[col] == Alive);
+
+            let orthogonal = alive(above, col) + alive(middle, col - 1) + alive(middle, col + 1) + alive(below, col);
+            match self.neighborhood {
+                Neighborhood::Moore => orthogonal + alive(above, col - 1) + alive(above, col + 1) + alive(below, col - 1) + alive(below, col + 1),
+                Neighborhood::VonNeumann => orthogonal,
+                Neighborhood::Hex => {
+                    let side = if row.is_multiple_of(2) { col - 1 } else { col + 1 };
+                    orthogonal + alive(above, side) + alive(below, side)
+                }
+            }
+        }
+    }
+
+    /// Copy `rows` into a `width` x `height` block of `fill`, with their top-left corner at `top`, `left`.
+    fn pad<T: Copy>(rows: &[Vec<T>], fill: T, top: usize, left: usize, width: usize, height: usize) -> Vec<Vec<T>> {
+        let mut padded = vec![vec![fill; width]; height];
+        for (padded_row, row) in padded[top..].iter_mut().zip(rows) {
+            padded_row[left..left + row.len()].copy_from_slice(row);
+        }
+        padded
+    }
+
+    /// Grids are equal when they have the same dimensions and cells; counters, settings and scratch space are ignored.
+    impl PartialEq for Grid {
+        fn eq(&self, other: &Self) -> bool {
+            self.cells == other.cells
+        }
+    }
+
+    impl Eq for Grid {}
+
+    impl Hash for Grid {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            let height = self.height();
+            let width = self.width();
+            state.write_usize(height);
+            state.write_usize(width);
+            self.hash_cells(state);
+        }
+    }
+
+    impl fmt::Display for Grid {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.to_ascii())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::colors::{Heatmap, Monochrome};
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        fn grid_with_alive_cells(width: usize, height: usize, alive_positions: &[(usize, usize)]) -> Grid {
+            let mut grid = Grid::new(width, height);
+            for &(row, col) in alive_positions {
+                grid.cells[row][col] = Alive;
+            }
+            grid.population = count_alive(&grid);
+            grid
+        }
+
+        fn count_alive(grid: &Grid) -> usize {
+            grid.cells.iter().flatten().filter(|cell| **cell == Alive).count()
+        }
+
+        #[test]
+        fn new_initializes_dead_cells() {
+            let grid = Grid::new(3, 2);
+            assert_eq!(grid.cells.len(), 2);
+            assert!(grid.cells.iter().all(|row| row.len() == 3));
+            assert!(grid.cells.iter().all(|row| row.iter().all(|cell| *cell == Dead)));
+        }
+
+        #[test]
+        fn default_grid_can_be_advanced() {
+            let mut grid = Grid::default();
+            assert_eq!((grid.width(), grid.height()), (3, 3));
+            for col in 0..3 {
+                grid.set(1, col, Alive);
+            }
+            assert!(grid.advance());
+            assert_eq!(grid.generation(), 1);
+        }
+
+        #[test]
+        fn randomize_symmetric_mirrors_across_the_requested_axes() {
+            let mut rng = StdRng::seed_from_u64(17);
+            for (width, height) in [(10, 8), (9, 7)] {
+                for symmetry in [Symmetry::Horizontal, Symmetry::Vertical, Symmetry::FourFold] {
+                    let mut grid = Grid::new(width, height);
+                    grid.randomize_symmetric(symmetry, &mut rng);
+                    let flipped_rows: Vec<_> = grid.cells.iter().rev().cloned().collect();
+                    let flipped_cols: Vec<_> = grid.cells.iter().map(|row| row.iter().rev().copied().collect::<Vec<_>>()).collect();
+                    let mirrored_rows = symmetry != Symmetry::Vertical;
+                    let mirrored_cols = symmetry != Symmetry::Horizontal;
+                    assert_eq!(grid.cells == flipped_rows, mirrored_rows, "{width}x{height} {symmetry:?}");
+                    assert_eq!(grid.cells == flipped_cols, mirrored_cols, "{width}x{height} {symmetry:?}");
+                    assert_eq!(grid.population(), count_alive(&grid));
+                }
+            }
+        }
+
+        #[test]
+        fn randomize_with_seed_is_deterministic() {
+            let mut grid = Grid::new(2, 3);
+            let mut rng = StdRng::seed_from_u64(42);
+            grid.randomize_with_density_rng(0.5, &mut rng);
+
+            let mut rng = StdRng::seed_from_u64(42);
+            let mut expected = vec![vec![Dead; 2]; 3];
+            for row in expected.iter_mut() {
+                for cell in row.iter_mut() {
+                    *cell = if rng.random_bool(0.5) { Alive } else { Dead };
+                }
+            }
+
+            assert_eq!(grid.cells, expected);
+            let alive_count = grid.cells.iter().flatten().filter(|cell| **cell == Alive).count();
+            assert!(alive_count > 0);
+            assert!(alive_count < grid.width() * grid.height());
+        }
+
+        #[test]
+        fn alive_neighbors_wraps_around_edges() {
+            let grid = grid_with_alive_cells(3, 3, &[(0, 2), (2, 0), (2, 2)]);
+            assert_eq!(grid.alive_neighbors(0, 0), 3);
+        }
+
+        #[test]
+        fn neighbor_counts_match_alive_neighbors() {
+            let mut grid = grid_with_alive_cells(5, 4, &[(0, 0), (0, 1), (1, 1), (2, 3), (3, 4)]);
+            for neighborhood in [Neighborhood::Moore, Neighborhood::VonNeumann, Neighborhood::Hex] {
+                grid.set_neighborhood(neighborhood);
+                let counts = grid.neighbor_counts();
+                assert_eq!(counts.len(), 4);
+                for (row, row_counts) in counts.iter().enumerate() {
+                    assert_eq!(row_counts.len(), 5);
+                    for (col, count) in row_counts.iter().enumerate() {
+                        assert_eq!(usize::from(*count), grid.alive_neighbors(row, col));
+                    }
+                }
+            }
+            // Von Neumann: only (0, 1). Moore adds (1, 1) and, wrapping diagonally, (3, 4); on even row 0, Hex adds
+            // (3, 4) above to the left but not (1, 1) below to the right.
+            assert_eq!(grid.neighbor_counts()[0][0], 2);
+            grid.set_neighborhood(Neighborhood::VonNeumann);
+            assert_eq!(grid.neighbor_counts()[0][0], 1);
+            grid.set_neighborhood(Neighborhood::Moore);
+            assert_eq!(grid.neighbor_counts()[0][0], 3);
+        }
+
+        #[test]
+        fn hex_neighborhood_leans_with_row_parity() {
+            // A ring of six around (2, 2) on an even row, plus the two cells Moore would add on its right.
+            let ring = [(2, 1), (2, 3), (1, 1), (1, 2), (3, 1), (3, 2)];
+            let mut grid = grid_with_alive_cells(6, 6, &ring);
+            grid.set(1, 3, Alive);
+            grid.set(3, 3, Alive);
+            grid.set_neighborhood(Neighborhood::Hex);
+            assert_eq!(grid.alive_neighbors(2, 2), 6);
+            // On odd row 3 the rows above and below lean right: (2, 3), (2, 4), (4, 3), (4, 4) and the sides.
+            assert_eq!(grid.alive_neighbors(3, 3), 2);
+            grid.set_neighborhood(Neighborhood::Moore);
+            assert_eq!(grid.alive_neighbors(2, 2), 8);
+        }
+
+        #[test]
+        fn hex_neighborhood_wraps_on_an_even_torus() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(3, 3), (3, 0), (1, 3), (0, 3), (1, 0)]);
+            grid.set_neighborhood(Neighborhood::Hex);
+            // (0, 0) is on an even row: its neighbors are (0, 3), (0, 1), (3, 3), (3, 0), (1, 3) and (1, 0).
+            assert_eq!(grid.alive_neighbors(0, 0), 5);
+            grid.set_edge_mode(EdgeMode::Dead);
+            assert_eq!(grid.alive_neighbors(0, 0), 1);
+        }
+
+        #[test]
+        fn hex_pattern_evolves_the_same_on_a_grid_that_grows_on_top() {
+            let pattern = [(0, 1), (0, 2), (1, 0), (1, 2), (2, 1)];
+            let cells_at = |top: usize, left: usize| pattern.map(|(row, col)| (row + top, col + left));
+            // Row 1 is inside the margin, so the auto-growing grid grows on top right away.
+            let mut growing = grid_with_alive_cells(10, 10, &cells_at(1, 4));
+            growing.set_auto_grow(Some(AutoGrow {
+                margin: 2,
+                max_width: 40,
+                max_height: 40,
+            }));
+            let mut fixed = grid_with_alive_cells(30, 30, &cells_at(11, 10));
+            for grid in [&mut growing, &mut fixed] {
+                grid.set_neighborhood(Neighborhood::Hex);
+                grid.set_edge_mode(EdgeMode::Dead);
+            }
+
+            let shape = |grid: &Grid| {
+                let (min_row, min_col, ..) = grid.alive_bounds().unwrap();
+                let cells: Vec<_> = grid.alive_cells().map(|(row, col)| (row - min_row, col - min_col)).collect();
+                (min_row % 2, cells)
+            };
+            for _ in 0..4 {
+                growing.advance();
+                fixed.advance();
+                assert_eq!(shape(&growing), shape(&fixed));
+            }
+            assert!(growing.height() > 10);
+        }
+
+        #[test]
+        fn dead_edges_do_not_wrap() {
+            let mut grid = grid_with_alive_cells(3, 3, &[(0, 2), (2, 0), (2, 2)]);
+            grid.set_edge_mode(EdgeMode::Dead);
+            assert_eq!(grid.alive_neighbors(0, 0), 0);
+            assert_eq!(grid.alive_neighbors(1, 1), 3);
+        }
+
+        #[test]
+        fn map_colors_passes_ages_to_the_scheme() {
+            let mut grid = grid_with_alive_cells(3, 3, &[(1, 1)]);
+            let monochrome = Monochrome::DEFAULT;
+            assert_eq!(grid.map_colors(&monochrome)[1], vec![monochrome.dead, monochrome.alive, monochrome.dead]);
+
+            let heatmap = Heatmap::DEFAULT;
+            assert_eq!(grid.map_colors(&heatmap)[1][1], heatmap.base.alive);
+            grid.set_age_tracking(true);
+            assert_eq!(grid.map_colors(&heatmap)[1][1], heatmap.newborn);
+        }
+
+        #[test]
+        fn dirty_rows_flags_only_the_rows_a_blinker_touches() {
+            let mut grid = Grid::new(5, 5);
+            for col in 1..4 {
+                grid.set(2, col, Alive);
+            }
+            assert!(grid.dirty_rows().is_empty());
+
+            assert!(grid.advance());
+            assert_eq!(grid.dirty_rows(), &[false, true, true, true, false]);
+        }
+
+        #[test]
+        fn r_pentomino_matches_known_generations() {
+            // Asymmetric enough that reading cells already updated this generation would change the outcome.
+            let mut grid = grid_with_alive_cells(9, 9, &[(3, 4), (3, 5), (4, 3), (4, 4), (5, 4)]);
+            for _ in 0..6 {
+                grid.advance();
+            }
+            let expected = concat!(
+                ".........\n",
+                ".........\n",
+                "...###...\n",
+                "..##.##..\n",
+                "..#..##..\n",
+                "...##....\n",
+                ".........\n",
+                ".........\n",
+                ".........\n",
+            );
+            assert_eq!(grid.to_ascii(), expected);
+            assert_eq!(grid.population(), 12);
+        }
+
+        #[test]
+        fn next_generation_matches_advance_and_leaves_the_grid_alone() {
+            let glider = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+            let mut grid = Grid::new(8, 8);
+            grid.stamp(&glider, 2, 2);
+            let start = grid.cells.clone();
+
+            let next = grid.next_generation();
+            assert_eq!(grid.cells, start);
+            assert_eq!(grid.generation(), 0);
+
+            grid.advance();
+            assert_eq!(next, grid);
+            assert_eq!(next.population(), grid.population());
+            assert_eq!(next.generation(), grid.generation());
+        }
+
+        #[test]
+        fn generations_of_a_blinker_alternate_between_two_phases() {
+            let blinker = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            let start = blinker.cells.clone();
+            let generations: Vec<Grid> = blinker.generations().take(5).collect();
+            assert_eq!(generations.len(), 5);
+            for (index, grid) in generations.iter().enumerate() {
+                assert_eq!(grid.generation(), index as u64);
+                assert_eq!(grid.cells == start, index % 2 == 0);
+            }
+        }
+
+        #[test]
+        fn generations_of_a_still_life_yield_only_the_initial_state() {
+            let block = grid_with_alive_cells(4, 4, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
+            let generations: Vec<Grid> = block.generations().collect();
+            assert_eq!(generations.len(), 1);
+            assert_eq!(generations[0].population(), 4);
+        }
+
+        #[test]
+        fn auto_grow_makes_room_for_a_glider_instead_of_letting_it_hit_the_edge() {
+            let glider = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+            let mut grid = Grid::new(10, 10);
+            grid.stamp(&glider, 2, 2);
+            grid.set_edge_mode(EdgeMode::Dead);
+            grid.set_auto_grow(Some(AutoGrow {
+                margin: 2,
+                max_width: 40,
+                max_height: 40,
+            }));
+
+            for _ in 0..60 {
+                grid.advance();
+                assert_eq!(grid.population(), 5);
+            }
+            assert!(grid.width() > 10 && grid.height() > 10);
+            // The glider flies towards the bottom-right corner, so the grid only grows on that side.
+            assert_eq!(
+                grid.alive_bounds().map(|(min_row, min_col, ..)| (min_row, min_col)),
+                Some((2 + 15, 2 + 15))
+            );
+        }
+
+        #[test]
+        fn auto_grow_stops_at_the_maximum_size() {
+            let glider = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+            let mut grid = Grid::new(10, 10);
+            grid.stamp(&glider, 2, 2);
+            grid.set_edge_mode(EdgeMode::Dead);
+            grid.set_auto_grow(Some(AutoGrow {
+                margin: 2,
+                max_width: 14,
+                max_height: 12,
+            }));
+
+            for _ in 0..80 {
+                grid.advance();
+            }
+            assert_eq!((grid.width(), grid.height()), (14, 12));
+            // With no room left the glider ends up as a block in the corner, as on a fixed-size board.
+            assert_eq!(grid.population(), 4);
+        }
+
+        #[test]
+        fn glider_dies_against_dead_edges_but_survives_on_a_torus() {
+            let glider = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+            let mut grids = [EdgeMode::Toroidal, EdgeMode::Dead].map(|edge_mode| {
+                let mut grid = Grid::new(8, 8);
+                grid.stamp(&glider, 0, 0);
+                grid.set_edge_mode(edge_mode);
+                grid
+            });
+            for grid in &mut grids {
+                for _ in 0..40 {
+                    grid.advance();
+                }
+            }
+            // On the torus the glider keeps flying; at a dead corner it turns into a block.
+            assert_eq!(grids[0].population(), 5);
+            assert_eq!(grids[1].population(), 4);
+            assert_eq!(grids[1].detect_cycle(2), Some(1));
+        }
+
+        #[test]
+        fn corner_cell_counts_neighbors_beyond_the_edges_by_edge_mode() {
+            // Alive in the corner, next to it, and in the opposite corners a torus wraps onto.
+            let mut grid = grid_with_alive_cells(4, 4, &[(0, 0), (0, 1), (1, 1), (3, 0), (3, 3)]);
+            let expected = [
+                (EdgeMode::Toroidal, 4),
+                (EdgeMode::Dead, 2),
+                // (0, 0) mirrors onto itself three times and (0, 1) once.
+                (EdgeMode::Reflect, 6),
+                (EdgeMode::FixedAlive, 7),
+            ];
+            for (edge_mode, count) in expected {
+                grid.set_edge_mode(edge_mode);
+                assert_eq!(grid.alive_neighbors(0, 0), count, "{edge_mode:?}");
+            }
+        }
+
+        #[test]
+        fn edge_modes_apply_to_each_axis_independently() {
+            // A cylinder: wrapped left and right, dead above and below.
+            let mut grid = Grid::new(5, 5).with_edge_modes(EdgeMode::Toroidal, EdgeMode::Dead);
+            grid.cells[0][4] = Alive;
+            grid.cells[1][4] = Alive;
+            grid.cells[4][0] = Alive;
+            grid.cells[4][1] = Alive;
+            // (0, 4) and (1, 4) are reached across the left edge, the bottom row only across the dead top edge.
+            assert_eq!(grid.alive_neighbors(0, 0), 2);
+            grid.set_edge_modes(EdgeMode::Dead, EdgeMode::Toroidal);
+            assert_eq!(grid.alive_neighbors(0, 0), 2);
+            grid.set_edge_mode(EdgeMode::Toroidal);
+            assert_eq!(grid.alive_neighbors(0, 0), 4);
+            assert_eq!(grid.edge_modes(), (EdgeMode::Toroidal, EdgeMode::Toroidal));
+        }
+
+        #[test]
+        fn glider_wraps_around_a_cylinder_but_dies_at_its_rim() {
+            let glider = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+            // Travelling down and right, the glider crosses the right edge before it reaches the bottom one.
+            let mut grid = Grid::new(8, 16).with_edge_modes(EdgeMode::Toroidal, EdgeMode::Dead);
+            grid.stamp(&glider, 0, 4);
+            for _ in 0..16 {
+                grid.advance();
+            }
+            assert_eq!(grid.population(), 5);
+            assert!(grid.cells.iter().any(|row| row[0] == Alive || row[1] == Alive));
+            for _ in 0..40 {
+                grid.advance();
+            }
+            assert_ne!(grid.population(), 5);
+        }
+
+        #[test]
+        fn fixed_alive_border_brings_an_empty_grid_to_life() {
+            let mut grid = Grid::new(3, 3);
+            grid.set_edge_mode(EdgeMode::FixedAlive);
+            assert!(grid.advance());
+            // Edge centers see exactly three border cells; corners see five.
+            assert_eq!(grid.to_ascii(), ".#.\n#.#\n.#.\n");
+        }
+
+        #[test]
+        fn interior_fast_path_matches_per_cell_neighbor_counting() {
+            let rules = [Rule::CONWAY, Rule::BRIANS_BRAIN];
+            for (seed, rule) in rules.into_iter().enumerate() {
+                for neighborhood in [Neighborhood::Moore, Neighborhood::VonNeumann, Neighborhood::Hex] {
+                    for edge_mode in [EdgeMode::Toroidal, EdgeMode::Dead, EdgeMode::Reflect, EdgeMode::FixedAlive] {
+                        let mut grid = Grid::new(37, 23);
+                        grid.set_rule(rule);
+                        grid.set_neighborhood(neighborhood);
+                        grid.set_edge_mode(edge_mode);
+                        grid.randomize_with_density_rng(0.4, &mut StdRng::seed_from_u64(seed as u64));
+                        for _ in 0..5 {
+                            let counts = grid.neighbor_counts();
+                            let expected: Vec<Vec<CellState>> = grid
+                                .cells
+                                .iter()
+                                .zip(&counts)
+                                .map(|(row, row_counts)| {
+                                    row.iter()
+                                        .zip(row_counts)
+                                        .map(|(&state, &count)| grid.next_state(state, usize::from(count), &mut 0, &mut 0))
+                                        .collect()
+                                })
+                                .collect();
+                            grid.advance();
+                            assert_eq!(grid.cells, expected, "{rule} {neighborhood:?} {edge_mode:?}");
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn advance_keeps_population_consistent_on_random_grids() {
+            // Property-style soak test: many small random grids, rules and settings, each advanced several times.
+            let edge_modes = [EdgeMode::Toroidal, EdgeMode::Dead, EdgeMode::Reflect, EdgeMode::FixedAlive];
+            let neighborhoods = [Neighborhood::Moore, Neighborhood::VonNeumann, Neighborhood::Hex];
+            let mut rng = StdRng::seed_from_u64(595);
+            for case in 0..300 {
+                let (width, height) = (rng.random_range(3..40), rng.random_range(3..40));
+                let birth: Vec<u8> = (0..=8).filter(|_| rng.random_bool(0.3)).collect();
+                let survival: Vec<u8> = (0..=8).filter(|_| rng.random_bool(0.3)).collect();
+                let rule = Rule::new(&birth, &survival, rng.random_range(2..6)).unwrap();
+                let mut grid = Grid::new(width, height);
+                grid.set_rule(rule);
+                grid.set_neighborhood(neighborhoods[rng.random_range(0..3)]);
+                grid.set_edge_modes(edge_modes[rng.random_range(0..4)], edge_modes[rng.random_range(0..4)]);
+                for cell in grid.cells.iter_mut().flatten() {
+                    *cell = match rng.random_range(0..rule.states()) {
+                        0 => Dead,
+                        1 => Alive,
+                        stage => Dying(stage - 1),
+                    };
+                }
+                grid.population = count_alive(&grid);
+
+                for _ in 0..8 {
+                    grid.advance();
+                    assert!(grid.population() <= width * height, "case {case}: {rule} {width}x{height}");
+                    assert_eq!(grid.population(), count_alive(&grid), "case {case}: {rule} {width}x{height}");
+                }
+            }
+        }
+
+        #[test]
+        fn alive_neighbors_counts_zero_for_isolated_cell() {
+            let grid = Grid::new(3, 3);
+            assert_eq!(grid.alive_neighbors(1, 1), 0);
+        }
+
+        #[test]
+        fn set_and_toggle_keep_population_in_sync() {
+            let mut grid = Grid::new(4, 4);
+            grid.set(1, 1, Alive);
+            grid.set(1, 1, Alive);
+            grid.toggle(2, 3);
+            assert_eq!(grid.population(), 2);
+
+            grid.toggle(1, 1);
+            grid.set(0, 0, Dead);
+            assert_eq!(grid.cells[1][1], Dead);
+            assert_eq!(grid.cells[2][3], Alive);
+            assert_eq!(grid.population(), 1);
+            assert_eq!(grid.population(), count_alive(&grid));
+        }
+
+        #[test]
+        fn resize_grow_preserves_content() {
+            let mut grid = grid_with_alive_cells(3, 3, &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+            let original = grid.cells.clone();
+            grid.resize(5, 4);
+
+            assert_eq!(grid.cells.len(), 4);
+            assert!(grid.cells.iter().all(|row| row.len() == 5));
+            for (row, original_row) in original.iter().enumerate() {
+                assert_eq!(&grid.cells[row][..3], &original_row[..]);
+                assert!(grid.cells[row][3..].iter().all(|cell| *cell == Dead));
+            }
+            assert!(grid.cells[3].iter().all(|cell| *cell == Dead));
+            assert_eq!(grid.population(), 5);
+            assert!(grid.advance());
+        }
+
+        #[test]
+        fn resize_shrink_clips_content() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(0, 0), (1, 1), (3, 0), (0, 3)]);
+            grid.resize(2, 2);
+
+            assert_eq!(grid.cells, vec![vec![Alive, Dead], vec![Dead, Alive]]);
+            assert_eq!(grid.population(), 2);
+            assert_eq!(grid.next_cells.len(), 2);
+            assert!(grid.next_cells.iter().all(|row| row.len() == 2));
+        }
+
+        #[test]
+        fn stamp_places_pattern_at_offset() {
+            let glider = grid_with_alive_cells(3, 3, &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+            let mut grid = Grid::new(50, 50);
+            grid.stamp(&glider, 10, 10);
+
+            let alive: Vec<_> = (0..50)
+                .flat_map(|row| (0..50).map(move |col| (row, col)))
+                .filter(|&(row, col)| grid.cells[row][col] == Alive)
+                .collect();
+            assert_eq!(alive, vec![(10, 11), (11, 12), (12, 10), (12, 11), (12, 12)]);
+            assert_eq!(grid.population(), 5);
+        }
+
+        #[test]
+        fn stamp_keeps_existing_cells_and_clips_at_edges() {
+            let block = grid_with_alive_cells(2, 2, &[(0, 0), (0, 1), (1, 0), (1, 1)]);
+            let mut grid = grid_with_alive_cells(4, 4, &[(3, 3), (0, 0)]);
+            grid.stamp(&block, 3, 3);
+            grid.stamp(&block, 10, 10);
+
+            assert_eq!(grid.population(), 2);
+            assert_eq!(count_alive(&grid), 2);
+            assert_eq!(grid.cells[3][3], Alive);
+            assert_eq!(grid.cells[0][0], Alive);
+        }
+
+        #[test]
+        fn glider_rotated_four_times_returns_to_itself() {
+            let glider = grid_with_alive_cells(3, 3, &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+            let once = glider.rotated(1);
+            assert_ne!(once, glider);
+            assert_eq!(once.population(), 5);
+            assert_eq!(once.rotated(1).rotated(1).rotated(1), glider);
+            assert_eq!(glider.rotated(4), glider);
+            assert_eq!(glider.rotated(2), once.rotated(1));
+        }
+
+        #[test]
+        fn quarter_turn_swaps_width_and_height() {
+            // An L in the top-left corner of a 4 wide, 2 tall grid.
+            let mut grid = grid_with_alive_cells(4, 2, &[(0, 0), (0, 1), (0, 2), (1, 0)]);
+            grid.set_edge_modes(EdgeMode::Dead, EdgeMode::Toroidal);
+            let turned = grid.rotated(1);
+
+            assert_eq!((turned.width(), turned.height()), (2, 4));
+            assert_eq!(turned.edge_modes(), (EdgeMode::Toroidal, EdgeMode::Dead));
+            // Clockwise, the top row becomes the right column.
+            let alive: Vec<_> = (0..4)
+                .flat_map(|row| (0..2).map(move |col| (row, col)))
+                .filter(|&(row, col)| turned.cells[row][col] == Alive)
+                .collect();
+            assert_eq!(alive, vec![(0, 0), (0, 1), (1, 1), (2, 1)]);
+            assert_eq!(grid.rotated(3), turned.rotated(2));
+        }
+
+        #[test]
+        fn flips_mirror_the_pattern_and_undo_themselves() {
+            let glider = grid_with_alive_cells(3, 3, &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+            let mirrored = glider.flipped(true);
+            assert_eq!(mirrored, grid_with_alive_cells(3, 3, &[(0, 1), (1, 0), (2, 0), (2, 1), (2, 2)]));
+            assert_eq!(mirrored.flipped(true), glider);
+            assert_eq!(glider.flipped(false).flipped(false), glider);
+            // Both flips together are a half turn.
+            assert_eq!(glider.flipped(true).flipped(false), glider.rotated(2));
+        }
+
+        #[test]
+        fn step_back_restores_previous_generations() {
+            let mut grid = Grid::with_history(5, 5, 8);
+            grid.stamp(&grid_with_alive_cells(3, 1, &[(0, 0), (0, 1), (0, 2)]), 2, 1);
+            grid.stamp(&grid_with_alive_cells(1, 1, &[(0, 0)]), 0, 0);
+
+            let mut states = vec![grid.cells.clone()];
+            for _ in 0..3 {
+                assert!(grid.advance());
+                states.push(grid.cells.clone());
+            }
+
+            states.pop();
+            while let Some(expected) = states.pop() {
+                assert!(grid.step_back());
+                assert_eq!(grid.cells, expected);
+                assert_eq!(grid.population(), count_alive(&grid));
+            }
+            assert_eq!(grid.generation(), 0);
+            assert!(!grid.step_back());
+        }
+
+        #[test]
+        fn history_is_bounded_by_depth() {
+            let mut grid = Grid::with_history(5, 5, 2);
+            grid.stamp(&grid_with_alive_cells(3, 1, &[(0, 0), (0, 1), (0, 2)]), 2, 1);
+            for _ in 0..5 {
+                grid.advance();
             }
+            assert_eq!(grid.history_len(), 2);
+            assert!(grid.step_back());
+            assert!(grid.step_back());
+            assert!(!grid.step_back());
         }
 
-        pub fn randomize(&mut self) {
-            let mut rng = rand::rng();
-            self.randomize_with_rng(&mut rng);
+        #[test]
+        fn history_bytes_stays_well_below_cell_storage() {
+            let mut grid = Grid::with_history(64, 64, 4);
+            assert_eq!(grid.history_bytes(), 0);
+            grid.stamp(&grid_with_alive_cells(3, 1, &[(0, 0), (0, 1), (0, 2)]), 30, 30);
+            for _ in 0..6 {
+                grid.advance();
+            }
+            assert_eq!(grid.history_len(), 4);
+            assert!(grid.history_bytes() > 0);
+            assert!(grid.history_bytes() < 4 * 64 * 64 / 10);
         }
 
-        fn randomize_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
-            for row in self.cells.iter_mut() {
-                for cell in row.iter_mut() {
-                    *cell = if rng.random_bool(0.5) { Alive } else { Dead };
-                }
+        #[test]
+        fn grid_without_history_cannot_step_back() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.advance();
+            assert!(!grid.step_back());
+        }
+
+        #[test]
+        fn restore_returns_to_the_snapshot_after_advancing() {
+            let mut grid = Grid::new(12, 10);
+            grid.randomize_with_density_rng(0.4, &mut StdRng::seed_from_u64(3));
+            let saved = grid.cells.clone();
+            let snapshot = grid.snapshot();
+            for _ in 0..5 {
+                grid.advance();
             }
+            assert_ne!(grid.cells, saved);
+
+            assert_eq!(grid.restore(&snapshot), Ok(()));
+            assert_eq!(grid.cells, saved);
+            assert_eq!(grid.population(), count_alive(&grid));
         }
 
-        /// Advance the grid by one step (Game of Life logic)
-        pub fn advance(&mut self) -> bool {
-            let height = self.cells.len();
-            let width = self.cells[0].len();
+        #[test]
+        fn restore_rejects_snapshots_of_another_size() {
+            let snapshot = Grid::new(4, 3).snapshot();
+            let mut grid = grid_with_alive_cells(3, 4, &[(1, 1)]);
+            assert_eq!(
+                grid.restore(&snapshot),
+                Err(GridError::SizeMismatch {
+                    snapshot: (4, 3),
+                    grid: (3, 4)
+                })
+            );
+            assert_eq!(grid.population(), 1);
+        }
 
-            for row_index in 0..height {
-                for col_index in 0..width {
-                    let alive_neighbors = self.alive_neighbors(row_index, col_index);
-                    let is_alive = self.cells[row_index][col_index];
+        #[test]
+        fn detect_cycle_reports_still_life() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
+            assert_eq!(grid.detect_cycle(10), Some(1));
+        }
 
-                    // Apply Game of Life rules
-                    self.next_cells[row_index][col_index] = match (is_alive, alive_neighbors) {
-                        (Alive, 2..=3) => Alive, // Survives
-                        (Dead, 3) => Alive,      // Becomes alive
-                        _ => Dead,               // Dies or remains dead
-                    };
-                }
+        #[test]
+        fn renders_cells_as_text() {
+            let blinker = grid_with_alive_cells(3, 1, &[(0, 0), (0, 1), (0, 2)]);
+            assert_eq!(blinker.to_ascii(), "###\n");
+            assert_eq!(blinker.to_string(), blinker.to_ascii());
+
+            let mut grid = grid_with_alive_cells(3, 2, &[(0, 1), (1, 2)]);
+            grid.set_rule(Rule::BRIANS_BRAIN);
+            grid.set(1, 0, Dying(1));
+            assert_eq!(grid.to_ascii(), ".#.\n..#\n");
+            assert_eq!(grid.to_unicode(), " █ \n  █\n");
+        }
+
+        #[test]
+        fn advance_by_counts_changing_steps_and_stops_when_static() {
+            let mut blinker = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            let start = blinker.cells.clone();
+            assert_eq!(blinker.advance_by(4), 4);
+            assert_eq!(blinker.cells, start);
+            assert_eq!(blinker.generation(), 4);
+
+            let mut block = grid_with_alive_cells(4, 4, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
+            assert_eq!(block.advance_by(10), 0);
+            assert_eq!(block.generation(), 0);
+        }
+
+        #[test]
+        fn detect_cycle_reports_blinker_period_and_restores_grid() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.advance();
+            let before = grid.cells.clone();
+
+            assert_eq!(grid.detect_cycle(10), Some(2));
+            assert_eq!(grid.cells, before);
+            assert_eq!(grid.generation(), 1);
+            assert_eq!(grid.population(), 3);
+        }
+
+        #[test]
+        fn detect_cycle_gives_up_after_max_period() {
+            // A glider needs 4 generations per diagonal step, so it returns home on an 8x8 torus after 32.
+            let mut grid = grid_with_alive_cells(8, 8, &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+            assert_eq!(grid.detect_cycle(10), None);
+            assert_eq!(grid.detect_cycle(40), Some(32));
+        }
+
+        #[test]
+        fn detect_cycle_ignores_other_still_lifes() {
+            // Three cells in an L become a block, which never returns to the starting state.
+            let mut grid = grid_with_alive_cells(6, 6, &[(2, 2), (2, 3), (3, 2)]);
+            assert_eq!(grid.detect_cycle(10), None);
+        }
+
+        #[test]
+        fn alive_cells_yields_pattern_coordinates() {
+            let positions = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+            let grid = grid_with_alive_cells(6, 6, &positions);
+            let alive: Vec<_> = grid.alive_cells().collect();
+            assert_eq!(alive, positions);
+            assert_eq!(grid.alive_cells().count(), grid.population());
+        }
+
+        #[test]
+        fn alive_cells_count_matches_population_after_advance() {
+            let mut grid = Grid::new(20, 20);
+            let mut rng = StdRng::seed_from_u64(5);
+            grid.randomize_with_density_rng(0.5, &mut rng);
+            for _ in 0..5 {
+                grid.advance();
+                assert_eq!(grid.alive_cells().count(), grid.population());
             }
+        }
 
-            if self.cells == self.next_cells {
-                return false;
+        #[test]
+        fn alive_bounds_of_single_cell() {
+            let grid = grid_with_alive_cells(10, 10, &[(4, 7)]);
+            assert_eq!(grid.alive_bounds(), Some((4, 7, 4, 7)));
+        }
+
+        #[test]
+        fn alive_bounds_of_scattered_cells() {
+            let grid = grid_with_alive_cells(10, 10, &[(2, 5), (3, 1), (6, 8), (6, 3)]);
+            assert_eq!(grid.alive_bounds(), Some((2, 1, 6, 8)));
+        }
+
+        #[test]
+        fn alive_bounds_of_empty_grid() {
+            assert_eq!(Grid::new(10, 10).alive_bounds(), None);
+        }
+
+        #[test]
+        fn touches_border_is_false_for_a_centered_pattern() {
+            let grid = grid_with_alive_cells(5, 5, &[(1, 1), (2, 2), (3, 3)]);
+            assert!(!grid.touches_border());
+            assert!(!Grid::new(5, 5).touches_border());
+        }
+
+        #[test]
+        fn touches_border_spots_a_cell_on_any_edge() {
+            for position in [(0, 2), (4, 2), (2, 0), (2, 4), (4, 4)] {
+                let grid = grid_with_alive_cells(5, 5, &[(2, 2), position]);
+                assert!(grid.touches_border(), "{position:?}");
             }
-            std::mem::swap(&mut self.cells, &mut self.next_cells);
-            true
         }
 
-        /// Count the number of alive neighbors for a cell
-        fn alive_neighbors(&self, row: usize, col: usize) -> usize {
-            let height = self.cells.len();
-            let width = self.cells[0].len();
-            let mut count = 0;
+        #[test]
+        fn count_alive_in_region_inside_the_grid() {
+            let grid = grid_with_alive_cells(5, 5, &[(0, 0), (1, 1), (1, 2), (2, 2), (4, 4)]);
+            assert_eq!(grid.count_alive_in_region(1, 1, 2, 2), 3);
+            assert_eq!(grid.count_alive_in_region(0, 0, 5, 5), grid.population());
+        }
 
-            // Unrolled neighbor checks for better performance
-            // Top row
-            let top = if row == 0 { height - 1 } else { row - 1 };
-            let bottom = if row == height - 1 { 0 } else { row + 1 };
-            let left = if col == 0 { width - 1 } else { col - 1 };
-            let right = if col == width - 1 { 0 } else { col + 1 };
+        #[test]
+        fn count_alive_in_region_clips_to_the_edges() {
+            let grid = grid_with_alive_cells(5, 5, &[(0, 0), (3, 3), (4, 4)]);
+            assert_eq!(grid.count_alive_in_region(3, 3, 10, 10), 2);
+            assert_eq!(grid.count_alive_in_region(4, 0, usize::MAX, usize::MAX), 1);
+            assert_eq!(grid.count_alive_in_region(5, 0, 3, 3), 0);
+        }
 
-            if self.cells[top][left] == Alive { count += 1; }
-            if self.cells[top][col] == Alive { count += 1; }
-            if self.cells[top][right] == Alive { count += 1; }
+        #[test]
+        fn count_alive_in_region_of_zero_size_is_empty() {
+            let grid = grid_with_alive_cells(3, 3, &[(1, 1)]);
+            assert_eq!(grid.count_alive_in_region(1, 1, 0, 1), 0);
+            assert_eq!(grid.count_alive_in_region(1, 1, 1, 0), 0);
+        }
 
-            if self.cells[row][left] == Alive { count += 1; }
-            if self.cells[row][right] == Alive { count += 1; }
+        #[test]
+        fn fill_region_sets_every_cell_in_the_rectangle_alive() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(1, 1)]);
+            grid.fill_region(1, 1, 2, 3, Alive);
+            assert_eq!(grid.to_ascii(), ".....\n.###.\n.###.\n.....\n.....\n");
+            assert_eq!(grid.population(), 6);
+        }
 
-            if self.cells[bottom][left] == Alive { count += 1; }
-            if self.cells[bottom][col] == Alive { count += 1; }
-            if self.cells[bottom][right] == Alive { count += 1; }
+        #[test]
+        fn fill_region_clears_to_dead() {
+            let mut grid = Grid::new(4, 4);
+            grid.fill_region(0, 0, 4, 4, Alive);
+            grid.fill_region(1, 0, 2, 4, Dead);
+            assert_eq!(grid.to_ascii(), "####\n....\n....\n####\n");
+            assert_eq!(grid.population(), 8);
+        }
 
-            count
+        #[test]
+        fn fill_region_clips_to_the_edges() {
+            let mut grid = Grid::new(4, 3);
+            grid.fill_region(2, 2, usize::MAX, 10, Alive);
+            assert_eq!(grid.to_ascii(), "....\n....\n..##\n");
+            grid.fill_region(3, 0, 2, 2, Alive);
+            grid.fill_region(0, 4, 2, 2, Alive);
+            assert_eq!(grid.population(), 2);
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use rand::{rngs::StdRng, Rng, SeedableRng};
+        #[test]
+        fn randomize_region_leaves_cells_outside_it_untouched() {
+            let mut grid = Grid::new(12, 10);
+            grid.fill_region(0, 0, 10, 12, Alive);
+            grid.fill_region(0, 0, 5, 6, Dead);
+            let before = grid.cells.clone();
+            let mut rng = StdRng::seed_from_u64(599);
+            grid.randomize_region(2, 3, 4, 5, 0.5, &mut rng);
 
-        fn grid_with_alive_cells(width: usize, height: usize, alive_positions: &[(usize, usize)]) -> Grid {
-            let mut grid = Grid::new(width, height);
-            for &(row, col) in alive_positions {
-                grid.cells[row][col] = Alive;
+            for (row, cells) in grid.cells.iter().enumerate() {
+                for (col, cell) in cells.iter().enumerate() {
+                    if !((2..6).contains(&row) && (3..8).contains(&col)) {
+                        assert_eq!(*cell, before[row][col], "cell {row}, {col} changed");
+                    }
+                }
             }
-            grid
+            assert_ne!(grid.cells, before);
+            assert_eq!(grid.population(), count_alive(&grid));
         }
 
         #[test]
-        fn new_initializes_dead_cells() {
-            let grid = Grid::new(3, 2);
-            assert_eq!(grid.cells.len(), 2);
-            assert!(grid.cells.iter().all(|row| row.len() == 3));
-            assert!(grid.cells.iter().all(|row| row.iter().all(|cell| *cell == Dead)));
+        fn randomize_region_clips_to_the_edges() {
+            let mut grid = Grid::new(4, 3);
+            let mut rng = StdRng::seed_from_u64(1);
+            grid.randomize_region(1, 2, usize::MAX, 10, 1.0, &mut rng);
+            assert_eq!(grid.to_ascii(), "....\n..##\n..##\n");
+            grid.randomize_region(0, 0, 3, 4, f64::NAN, &mut rng);
+            assert_eq!(grid.population(), 0);
         }
 
         #[test]
-        fn randomize_with_seed_is_deterministic() {
-            let mut grid = Grid::new(2, 3);
-            let mut rng = StdRng::seed_from_u64(42);
-            grid.randomize_with_rng(&mut rng);
-
-            let mut rng = StdRng::seed_from_u64(42);
-            let mut expected = vec![vec![Dead; 2]; 3];
-            for row in expected.iter_mut() {
-                for cell in row.iter_mut() {
-                    *cell = if rng.random_bool(0.5) { Alive } else { Dead };
+        fn population_history_records_each_generation() {
+            // A blinker keeps three cells in both phases, a beacon alternates between eight and six.
+            let mut blinker = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            let mut beacon = Grid::with_history(6, 6, 8);
+            beacon.stamp(
+                &grid_with_alive_cells(4, 4, &[(0, 0), (0, 1), (1, 0), (1, 1), (2, 2), (2, 3), (3, 2), (3, 3)]),
+                0,
+                0,
+            );
+            for grid in [&mut blinker, &mut beacon] {
+                grid.set_population_history(4);
+                assert!(grid.population_history().is_empty());
+                for _ in 0..6 {
+                    grid.advance();
                 }
             }
+            assert_eq!(*blinker.population_history(), [3, 3, 3, 3]);
+            assert_eq!(*beacon.population_history(), [6, 8, 6, 8]);
 
-            assert_eq!(grid.cells, expected);
-            let alive_count = grid.cells.iter().flatten().filter(|cell| **cell == Alive).count();
-            assert!(alive_count > 0);
-            assert!(alive_count < grid.cells.len() * grid.cells[0].len());
+            assert!(beacon.step_back());
+            assert_eq!(*beacon.population_history(), [6, 8, 6]);
+            beacon.set_population_history(2);
+            assert_eq!(*beacon.population_history(), [8, 6]);
         }
 
         #[test]
-        fn alive_neighbors_wraps_around_edges() {
-            let grid = grid_with_alive_cells(3, 3, &[(0, 2), (2, 0), (2, 2)]);
-            assert_eq!(grid.alive_neighbors(0, 0), 3);
+        fn population_history_starts_over_on_clear_and_randomize() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.set_population_history(10);
+            grid.advance();
+            grid.clear();
+            assert!(grid.population_history().is_empty());
+
+            grid.randomize();
+            grid.advance();
+            assert_eq!(*grid.population_history(), [grid.population()]);
+            grid.randomize();
+            assert!(grid.population_history().is_empty());
+
+            // Looking for a cycle leaves no trace.
+            grid.detect_cycle(4);
+            assert!(grid.population_history().is_empty());
+            grid.set_population_history(0);
+            grid.advance();
+            assert!(grid.population_history().is_empty());
         }
 
         #[test]
-        fn alive_neighbors_counts_zero_for_isolated_cell() {
-            let grid = Grid::new(3, 3);
+        fn difference_of_identical_grids_is_empty() {
+            let grid = grid_with_alive_cells(5, 4, &[(0, 1), (2, 3), (3, 4)]);
+            let same = grid_with_alive_cells(5, 4, &[(0, 1), (2, 3), (3, 4)]);
+            assert_eq!(grid.difference(&same), Ok(vec![]));
+            assert_eq!(Grid::new(3, 3).difference(&Grid::new(3, 3)), Ok(vec![]));
+        }
+
+        #[test]
+        fn difference_finds_a_single_changed_cell() {
+            let grid = grid_with_alive_cells(5, 4, &[(0, 1), (2, 3)]);
+            let mut other = grid_with_alive_cells(5, 4, &[(0, 1), (2, 3)]);
+            other.set(3, 2, Alive);
+            assert_eq!(grid.difference(&other), Ok(vec![(3, 2)]));
+            other.set(3, 2, Dying(1));
+            assert_eq!(other.difference(&grid), Ok(vec![(3, 2)]));
+        }
+
+        #[test]
+        fn difference_rejects_grids_of_another_size() {
+            let grid = Grid::new(5, 4);
+            assert_eq!(
+                grid.difference(&Grid::new(4, 5)),
+                Err(GridError::DimensionMismatch {
+                    expected: (5, 4),
+                    found: (4, 5)
+                })
+            );
+        }
+
+        fn hash_of(grid: &Grid) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            grid.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        #[test]
+        fn grids_with_same_cells_are_equal_and_hash_equal() {
+            let blinker = [(2, 1), (2, 2), (2, 3)];
+            let fresh = grid_with_alive_cells(5, 5, &blinker);
+            let mut advanced = grid_with_alive_cells(5, 5, &blinker);
+            advanced.advance();
+            assert_ne!(fresh, advanced);
+            advanced.advance();
+
+            assert_eq!(advanced.generation(), 2);
+            assert_eq!(fresh, advanced);
+            assert_eq!(hash_of(&fresh), hash_of(&advanced));
+        }
+
+        #[test]
+        fn grids_with_different_dimensions_are_not_equal() {
+            let small = Grid::new(4, 4);
+            let wide = Grid::new(5, 4);
+            assert_ne!(small, wide);
+            assert_ne!(hash_of(&small), hash_of(&wide));
+        }
+
+        #[test]
+        fn von_neumann_ignores_diagonal_neighbors() {
+            let mut grid = grid_with_alive_cells(3, 3, &[(0, 0), (0, 2), (2, 0), (2, 2)]);
+            assert_eq!(grid.alive_neighbors(1, 1), 4);
+            grid.set_neighborhood(Neighborhood::VonNeumann);
             assert_eq!(grid.alive_neighbors(1, 1), 0);
         }
 
+        #[test]
+        fn von_neumann_counts_orthogonal_neighbors_with_wrap() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(3, 0), (0, 3), (1, 0), (0, 1), (1, 1)]);
+            grid.set_neighborhood(Neighborhood::VonNeumann);
+            assert_eq!(grid.alive_neighbors(0, 0), 4);
+        }
+
         #[test]
         fn advance_returns_false_for_static_pattern() {
             let mut grid = grid_with_alive_cells(4, 4, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
             assert!(!grid.advance());
+            assert_eq!(grid.advance_status(), AdvanceOutcome::Static);
+            assert_eq!(grid.generation(), 0);
+        }
+
+        #[test]
+        fn advance_status_reports_extinction() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(1, 1), (3, 3)]);
+            assert_eq!(grid.advance_status(), AdvanceOutcome::Extinct);
+            assert_eq!(grid.generation(), 1);
+            // An empty grid stays extinct without counting further generations.
+            assert_eq!(grid.advance_status(), AdvanceOutcome::Extinct);
+            assert_eq!(grid.generation(), 1);
+        }
+
+        #[test]
+        fn advance_status_reports_changes() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            assert_eq!(grid.advance_status(), AdvanceOutcome::Changed);
+            assert_eq!(grid.generation(), 1);
+        }
+
+        #[test]
+        fn from_cells_accepts_rectangular_rows() {
+            let grid = Grid::from_cells(vec![vec![Dead, Alive, Dead], vec![Alive, Alive, Dead]]).unwrap();
+            assert_eq!(grid.cells, vec![vec![Dead, Alive, Dead], vec![Alive, Alive, Dead]]);
+            assert_eq!(grid.population(), 3);
+            assert_eq!(grid.next_cells, vec![vec![Dead; 3]; 2]);
+        }
+
+        #[test]
+        fn from_cells_rejects_ragged_rows() {
+            let error = Grid::from_cells(vec![vec![Dead, Alive], vec![Alive, Alive], vec![Dead]]).unwrap_err();
+            assert_eq!(
+                error,
+                GridError::RaggedRow {
+                    row: 2,
+                    expected: 2,
+                    found: 1
+                }
+            );
+            assert_eq!(error.to_string(), "row 2 has 1 cells, expected 2");
+        }
+
+        #[test]
+        fn from_cells_rejects_empty_input() {
+            assert_eq!(Grid::from_cells(Vec::new()).unwrap_err(), GridError::Empty);
+            assert_eq!(Grid::from_cells(vec![Vec::new(), Vec::new()]).unwrap_err(), GridError::Empty);
+        }
+
+        #[test]
+        fn width_and_height_report_dimensions() {
+            for (width, height) in [(3, 3), (7, 2), (1, 9), (0, 4), (4, 0), (0, 0)] {
+                let grid = Grid::new(width, height);
+                assert_eq!(grid.width(), if height == 0 { 0 } else { width });
+                assert_eq!(grid.height(), height);
+            }
+        }
+
+        #[test]
+        fn advance_is_a_no_op_on_empty_grids() {
+            for (width, height) in [(0, 0), (0, 5), (5, 0)] {
+                let mut grid = Grid::new(width, height);
+                assert!(!grid.advance());
+                assert!(grid.advance_with_changes().is_empty());
+                assert_eq!(grid.generation(), 0);
+            }
+        }
+
+        #[test]
+        fn advance_with_changes_lists_flipped_cells() {
+            // A horizontal blinker turns vertical: both ends die and the cells above and below the center are born.
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            let changes = grid.advance_with_changes();
+            assert_eq!(changes, vec![(1, 2, Alive), (2, 1, Dead), (2, 3, Dead), (3, 2, Alive)]);
+            assert_eq!(grid.generation(), 1);
+            assert_eq!(grid.population(), 3);
+        }
+
+        #[test]
+        fn advance_with_changes_is_empty_for_static_pattern() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
+            assert!(grid.advance_with_changes().is_empty());
+            assert_eq!(grid.generation(), 0);
         }
 
         #[test]
@@ -181,6 +2278,248 @@ pub mod grid {
             assert_eq!(grid.cells[1][1], Alive);
         }
 
+        #[test]
+        fn new_grid_has_zero_population() {
+            let grid = Grid::new(5, 4);
+            assert_eq!(grid.population(), 0);
+        }
+
+        #[test]
+        fn randomize_sets_population() {
+            let mut grid = Grid::new(16, 16);
+            let mut rng = StdRng::seed_from_u64(7);
+            grid.randomize_with_density_rng(0.5, &mut rng);
+            assert_eq!(grid.population(), count_alive(&grid));
+        }
+
+        #[test]
+        fn clear_resets_population() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(0, 0), (1, 2), (3, 3)]);
+            assert_eq!(grid.population(), 3);
+            grid.clear();
+            assert_eq!(grid.population(), 0);
+            assert_eq!(count_alive(&grid), 0);
+        }
+
+        #[test]
+        fn population_tracks_brute_force_count_over_generations() {
+            let mut grid = Grid::new(32, 24);
+            let mut rng = StdRng::seed_from_u64(1234);
+            grid.randomize_with_density_rng(0.5, &mut rng);
+
+            for _ in 0..50 {
+                grid.advance();
+                assert_eq!(grid.population(), count_alive(&grid));
+            }
+        }
+
+        #[test]
+        fn generation_counts_changing_steps() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            assert_eq!(grid.generation(), 0);
+            for expected in 1..=3 {
+                assert!(grid.advance());
+                assert_eq!(grid.generation(), expected);
+            }
+        }
+
+        #[test]
+        fn generation_does_not_count_static_steps() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
+            assert!(!grid.advance());
+            assert_eq!(grid.generation(), 0);
+        }
+
+        #[test]
+        fn clear_and_randomize_reset_generation() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.advance();
+            grid.clear();
+            assert_eq!(grid.generation(), 0);
+
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.advance();
+            let mut rng = StdRng::seed_from_u64(3);
+            grid.randomize_with_density_rng(0.5, &mut rng);
+            assert_eq!(grid.generation(), 0);
+        }
+
+        #[test]
+        fn brians_brain_cells_pass_through_dying_before_death() {
+            let mut grid = grid_with_alive_cells(6, 6, &[(2, 2), (2, 3)]);
+            grid.set_rule(Rule::BRIANS_BRAIN);
+
+            assert!(grid.advance());
+            // Nothing survives under B2/S; both cells start dying and the cells seeing exactly two are born.
+            assert_eq!(grid.cells[2][2], Dying(1));
+            assert_eq!(grid.cells[2][3], Dying(1));
+            assert_eq!(grid.cells[1][2], Alive);
+            assert_eq!(grid.cells[3][3], Alive);
+            assert_eq!(grid.population(), count_alive(&grid));
+
+            assert!(grid.advance());
+            // Dying cells decay to dead and were not eligible for birth in the meantime.
+            assert_eq!(grid.cells[2][2], Dead);
+            assert_eq!(grid.cells[2][3], Dead);
+            assert_eq!(grid.population(), count_alive(&grid));
+        }
+
+        #[test]
+        fn classic_rule_never_produces_dying_cells() {
+            let mut grid = Grid::new(30, 20);
+            let mut rng = StdRng::seed_from_u64(11);
+            grid.randomize_with_density_rng(0.5, &mut rng);
+            for _ in 0..20 {
+                grid.advance();
+                assert!(grid.cells.iter().flatten().all(|cell| matches!(cell, Alive | Dead)));
+            }
+        }
+
+        #[test]
+        fn set_rule_drops_unsupported_dying_stages() {
+            let mut grid = Grid::new(4, 4);
+            grid.set_rule(Rule::new(&[2], &[], 4).unwrap());
+            grid.cells[0][0] = Dying(2);
+            grid.cells[0][1] = Dying(1);
+
+            grid.set_rule(Rule::BRIANS_BRAIN);
+            assert_eq!(grid.cells[0][0], Dead);
+            assert_eq!(grid.cells[0][1], Dying(1));
+
+            grid.set_rule(Rule::CONWAY);
+            assert_eq!(grid.cells[0][1], Dead);
+        }
+
+        #[test]
+        fn set_keeps_population_in_sync_with_dying_cells() {
+            let mut grid = grid_with_alive_cells(4, 4, &[(1, 1)]);
+            grid.set(1, 1, Dying(1));
+            assert_eq!(grid.population(), 0);
+            grid.set(1, 1, Alive);
+            assert_eq!(grid.population(), 1);
+        }
+
+        #[test]
+        fn randomize_with_density_extremes_fill_or_empty_the_grid() {
+            let mut grid = grid_with_alive_cells(10, 8, &[(1, 1), (2, 2)]);
+            let mut rng = StdRng::seed_from_u64(5);
+
+            grid.randomize_with_density_rng(0.0, &mut rng);
+            assert_eq!(grid.population(), 0);
+            assert_eq!(count_alive(&grid), 0);
+
+            grid.randomize_with_density_rng(1.0, &mut rng);
+            assert_eq!(grid.population(), 80);
+            assert_eq!(count_alive(&grid), 80);
+        }
+
+        #[test]
+        fn randomize_seeded_is_reproducible() {
+            let mut first = Grid::new(20, 15);
+            let mut second = grid_with_alive_cells(20, 15, &[(0, 0)]);
+            first.randomize_seeded(42);
+            second.randomize_seeded(42);
+            assert_eq!(first, second);
+            assert_eq!(first.population(), second.population());
+
+            second.randomize_seeded(43);
+            assert_ne!(first, second);
+        }
+
+        #[test]
+        fn randomize_with_density_clamps_out_of_range_values() {
+            let mut grid = Grid::new(10, 8);
+            grid.randomize_with_density(7.5);
+            assert_eq!(grid.population(), 80);
+            grid.randomize_with_density(-1.0);
+            assert_eq!(grid.population(), 0);
+            grid.randomize_with_density(f64::NAN);
+            assert_eq!(grid.population(), 0);
+        }
+
+        #[test]
+        fn age_is_none_without_tracking() {
+            let grid = grid_with_alive_cells(5, 5, &[(2, 2)]);
+            assert!(!grid.tracks_ages());
+            assert_eq!(grid.age(2, 2), None);
+        }
+
+        #[test]
+        fn ages_grow_on_survival_and_reset_on_death() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.set_age_tracking(true);
+
+            grid.advance();
+            // The blinker's center survives while its ends die and two new cells are born.
+            assert_eq!(grid.age(2, 2), Some(1));
+            assert_eq!(grid.age(1, 2), Some(0));
+            assert_eq!(grid.age(2, 1), Some(0));
+
+            grid.advance();
+            assert_eq!(grid.age(2, 2), Some(2));
+            assert_eq!(grid.age(2, 1), Some(0));
+            assert_eq!(grid.age(1, 2), Some(0));
+        }
+
+        #[test]
+        fn editing_a_cell_resets_its_age() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.set_age_tracking(true);
+            grid.advance();
+            assert_eq!(grid.age(2, 2), Some(1));
+
+            grid.toggle(2, 2);
+            assert_eq!(grid.age(2, 2), Some(0));
+            grid.toggle(2, 2);
+            assert_eq!(grid.age(2, 2), Some(0));
+        }
+
+        #[test]
+        fn detect_cycle_leaves_ages_untouched() {
+            let mut grid = grid_with_alive_cells(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+            grid.set_age_tracking(true);
+            grid.advance();
+
+            assert_eq!(grid.detect_cycle(10), Some(2));
+            assert_eq!(grid.age(2, 2), Some(1));
+        }
+
+        #[test]
+        fn detect_cycle_does_not_grow_an_auto_growing_grid() {
+            let glider = Grid::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+            let mut grid = Grid::new(10, 10);
+            grid.stamp(&glider, 2, 2);
+            grid.set_edge_mode(EdgeMode::Dead);
+            grid.set_auto_grow(Some(AutoGrow {
+                margin: 2,
+                max_width: 40,
+                max_height: 40,
+            }));
+
+            // Long enough for the glider to reach the margin, which would have grown the grid.
+            assert_eq!(grid.detect_cycle(30), None);
+            assert_eq!((grid.width(), grid.height()), (10, 10));
+            assert!(grid.auto_grow().is_some());
+            assert!(grid.advance());
+            assert_eq!(grid.population(), 5);
+        }
+
+        #[test]
+        #[cfg(feature = "parallel")]
+        fn parallel_advance_matches_serial() {
+            let mut grid = Grid::new(300, 200);
+            let mut rng = StdRng::seed_from_u64(2024);
+            grid.randomize_with_density_rng(0.5, &mut rng);
+
+            for _ in 0..10 {
+                let mut serial = grid.next_cells.clone();
+                let mut parallel = grid.next_cells.clone();
+                assert_eq!(grid.compute_next_serial(&mut serial), grid.compute_next_parallel(&mut parallel));
+                assert_eq!(serial, parallel);
+                grid.advance();
+            }
+        }
+
         #[test]
         #[ignore] // Run with: cargo test --release -- --ignored --nocapture
         fn benchmark_advance_performance() {
@@ -193,7 +2532,7 @@ pub mod grid {
             // Create a grid with reproducible random state
             let mut grid = Grid::new(GRID_WIDTH, GRID_HEIGHT);
             let mut rng = StdRng::seed_from_u64(12345);
-            grid.randomize_with_rng(&mut rng);
+            grid.randomize_with_density_rng(0.5, &mut rng);
 
             // Warm up
             for _ in 0..10 {
@@ -203,7 +2542,7 @@ pub mod grid {
             // Reset to initial state for actual benchmark
             grid = Grid::new(GRID_WIDTH, GRID_HEIGHT);
             let mut rng = StdRng::seed_from_u64(12345);
-            grid.randomize_with_rng(&mut rng);
+            grid.randomize_with_density_rng(0.5, &mut rng);
 
             // Benchmark
             let start = Instant::now();
@@ -0,0 +1,70 @@
+//! A few classic patterns bundled as RLE, for demos and pattern pickers.
+use crate::grid::Grid;
+
+pub struct Pattern {
+    pub name: &'static str,
+    pub rle: &'static str,
+}
+
+impl Pattern {
+    /// Parse the pattern into a grid exactly its size.
+    pub fn grid(&self) -> Grid {
+        Grid::from_rle(self.rle).expect("bundled patterns are valid RLE")
+    }
+}
+
+pub const GLIDER: Pattern = Pattern {
+    name: "Glider",
+    rle: "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!",
+};
+
+/// Lightweight spaceship, travelling left.
+pub const LWSS: Pattern = Pattern {
+    name: "Lightweight spaceship",
+    rle: "x = 5, y = 4, rule = B3/S23\nbo2bo$o$o3bo$4o!",
+};
+
+/// Period-3 oscillator.
+pub const PULSAR: Pattern = Pattern {
+    name: "Pulsar",
+    rle: "x = 13, y = 13, rule = B3/S23\n\
+          2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$\n\
+          o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+};
+
+/// Emits a new glider every 30 generations.
+pub const GOSPER_GLIDER_GUN: Pattern = Pattern {
+    name: "Gosper glider gun",
+    rle: "x = 36, y = 9, rule = B3/S23\n\
+          24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$\n\
+          10bo5bo7bo$11bo3bo$12b2o!",
+};
+
+/// Every bundled pattern, smallest first.
+pub const ALL: [Pattern; 4] = [GLIDER, LWSS, PULSAR, GOSPER_GLIDER_GUN];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_patterns_parse_to_their_known_sizes() {
+        let expected = [(3, 3, 5), (5, 4, 9), (13, 13, 48), (36, 9, 36)];
+        for (pattern, (width, height, population)) in ALL.iter().zip(expected) {
+            let grid = pattern.grid();
+            assert_eq!(
+                (grid.width(), grid.height(), grid.population()),
+                (width, height, population),
+                "{}",
+                pattern.name
+            );
+        }
+    }
+
+    #[test]
+    fn pulsar_oscillates_with_period_three() {
+        let mut grid = Grid::new(17, 17);
+        grid.stamp(&PULSAR.grid(), 2, 2);
+        assert_eq!(grid.detect_cycle(10), Some(3));
+    }
+}